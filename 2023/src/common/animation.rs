@@ -0,0 +1,206 @@
+use crate::common::debug;
+use crate::common::grid::{Grid, GridRenderer, ImageExporter};
+use std::fmt::Display;
+use std::fs::{self, File};
+use std::io;
+use std::path::Path;
+use std::thread;
+use std::time::Duration;
+
+// GIF dimensions and delays are 16-bit.
+fn to_u16<N: TryInto<u16>>(value: N, what: &str) -> io::Result<u16> {
+    value.try_into().map_err(|_| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("{what} is too large for a GIF"),
+        )
+    })
+}
+
+// Records snapshots of a grid simulation for playback in the terminal or export as images.
+pub struct Recorder<T> {
+    frames: Vec<Grid<T>>,
+    enabled: bool,
+}
+
+impl<T: Clone> Default for Recorder<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Clone> Recorder<T> {
+    pub fn new() -> Self {
+        Self {
+            frames: vec![],
+            enabled: true,
+        }
+    }
+
+    // A recorder that only captures frames when debug output is enabled, so days can record
+    // unconditionally without paying for it in normal runs.
+    pub fn when_debugging() -> Self {
+        Self {
+            frames: vec![],
+            enabled: debug::is_enabled(),
+        }
+    }
+
+    pub fn record(&mut self, grid: &Grid<T>) {
+        if self.enabled {
+            self.frames.push(grid.clone());
+        }
+    }
+
+    pub fn frames(&self) -> &[Grid<T>] {
+        &self.frames
+    }
+
+    // Play back the frames in the terminal, redrawing in place.
+    pub fn play(&self, renderer: &GridRenderer, frame_delay: Duration)
+    where
+        T: Display,
+    {
+        for (i, frame) in self.frames.iter().enumerate() {
+            print!("\x1b[2J\x1b[H");
+            renderer.print(frame);
+            println!("Frame {}/{}", i + 1, self.frames.len());
+            thread::sleep(frame_delay);
+        }
+    }
+
+    // Write an animated GIF that loops forever. All frames must have the same dimensions.
+    pub fn write_gif<P: AsRef<Path>>(
+        &self,
+        exporter: &ImageExporter<T>,
+        path: P,
+        frame_delay: Duration,
+    ) -> io::Result<()> {
+        let Some(first_frame) = self.frames.first() else {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "No frames recorded",
+            ));
+        };
+        let (width, height) = exporter.dimensions(first_frame);
+        let (gif_width, gif_height) = (to_u16(width, "Width")?, to_u16(height, "Height")?);
+        // GIF delays are in hundredths of a second.
+        let delay = to_u16(frame_delay.as_millis() / 10, "Frame delay")?;
+
+        let mut encoder = gif::Encoder::new(File::create(path)?, gif_width, gif_height, &[])
+            .map_err(io::Error::other)?;
+        encoder
+            .set_repeat(gif::Repeat::Infinite)
+            .map_err(io::Error::other)?;
+        for frame in &self.frames {
+            if exporter.dimensions(frame) != (width, height) {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "Frames have different dimensions",
+                ));
+            }
+            let mut gif_frame =
+                gif::Frame::from_rgb_speed(gif_width, gif_height, &exporter.to_pixels(frame), 10);
+            gif_frame.delay = delay;
+            encoder.write_frame(&gif_frame).map_err(io::Error::other)?;
+        }
+        Ok(())
+    }
+
+    // Write each frame as a separate image named frame_0000.{extension} etc. in the directory.
+    pub fn write_frames<P: AsRef<Path>>(
+        &self,
+        exporter: &ImageExporter<T>,
+        dir: P,
+        extension: &str,
+    ) -> io::Result<()> {
+        fs::create_dir_all(&dir)?;
+        for (i, frame) in self.frames.iter().enumerate() {
+            exporter.write(
+                frame,
+                dir.as_ref().join(format!("frame_{i:04}.{extension}")),
+            )?;
+        }
+        Ok(())
+    }
+
+    // Write the frames as `{name}.gif` into the debug output directory, if enabled.
+    pub fn write_debug(&self, exporter: &ImageExporter<T>, name: &str, frame_delay: Duration) {
+        if self.frames.is_empty() {
+            return;
+        }
+        if let Some(path) = debug::output_path(&format!("{name}.gif")) {
+            if let Err(e) = self.write_gif(exporter, &path, frame_delay) {
+                println!("Failed to write {}: {e}", path.display());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::grid::{parse_grid, Color};
+    use googletest::prelude::*;
+    use std::env;
+
+    #[googletest::test]
+    fn records_frames_and_writes_gif() {
+        let mut recorder = Recorder::new();
+        let mut grid = parse_grid("..\n..");
+        recorder.record(&grid);
+        grid[0][0] = '#';
+        recorder.record(&grid);
+
+        expect_that!(recorder.frames().len(), eq(2));
+        expect_that!(recorder.frames()[0][0][0], eq('.'));
+
+        let exporter = ImageExporter::new(|c: &char| match c {
+            '#' => Color::White,
+            _ => Color::Black,
+        });
+        let path = env::temp_dir().join("animation_records_frames_and_writes_gif.gif");
+        expect_that!(
+            recorder.write_gif(&exporter, &path, Duration::from_millis(100)),
+            ok(anything())
+        );
+        expect_that!(
+            fs::read(&path).unwrap()[..6].to_vec(),
+            eq(b"GIF89a".to_vec())
+        );
+    }
+
+    #[googletest::test]
+    fn rejects_frames_with_different_dimensions() {
+        let mut recorder = Recorder::new();
+        recorder.record(&parse_grid(".."));
+        recorder.record(&parse_grid("..."));
+
+        let exporter = ImageExporter::new(|_: &char| Color::Black);
+        let path = env::temp_dir().join("animation_rejects_frames_with_different_dimensions.gif");
+        expect_that!(
+            recorder.write_gif(&exporter, &path, Duration::from_millis(100)),
+            err(anything())
+        );
+    }
+
+    #[googletest::test]
+    fn rejects_frames_too_large_for_a_gif() {
+        let exporter = ImageExporter::new(|_: &char| Color::Black);
+        let path = env::temp_dir().join("animation_rejects_frames_too_large_for_a_gif.gif");
+
+        let mut wide = Recorder::new();
+        wide.record(&parse_grid(&".".repeat(65536)));
+        expect_that!(
+            wide.write_gif(&exporter, &path, Duration::from_millis(100)),
+            err(anything())
+        );
+
+        let mut slow = Recorder::new();
+        slow.record(&parse_grid(".."));
+        expect_that!(
+            slow.write_gif(&exporter, &path, Duration::from_secs(1000)),
+            err(anything())
+        );
+    }
+}
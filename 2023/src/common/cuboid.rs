@@ -0,0 +1,187 @@
+use crate::common::point::{Point, Point3};
+use num::Integer;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub enum Axis {
+    X,
+    Y,
+    Z,
+}
+
+// An axis-aligned rectangle of lattice points, with inclusive bounds on both ends.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub struct Rect<T> {
+    pub min: Point<T>,
+    pub max: Point<T>,
+}
+
+impl<T> Rect<T>
+where
+    T: Integer + Copy,
+{
+    // Rectangle spanning both corners, in any order.
+    pub fn new(a: Point<T>, b: Point<T>) -> Self {
+        Self {
+            min: Point(a.0.min(b.0), a.1.min(b.1)),
+            max: Point(a.0.max(b.0), a.1.max(b.1)),
+        }
+    }
+
+    pub fn contains(&self, point: &Point<T>) -> bool {
+        self.min.0 <= point.0
+            && point.0 <= self.max.0
+            && self.min.1 <= point.1
+            && point.1 <= self.max.1
+    }
+
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        let min = Point(self.min.0.max(other.min.0), self.min.1.max(other.min.1));
+        let max = Point(self.max.0.min(other.max.0), self.max.1.min(other.max.1));
+        if min.0 <= max.0 && min.1 <= max.1 {
+            Some(Self { min, max })
+        } else {
+            None
+        }
+    }
+
+    // Number of lattice points in the rectangle.
+    pub fn area(&self) -> T {
+        (self.max.0 - self.min.0 + T::one()) * (self.max.1 - self.min.1 + T::one())
+    }
+
+    // All lattice points in the rectangle, in row-major order.
+    pub fn points(&self) -> impl Iterator<Item = Point<T>>
+    where
+        T: num::ToPrimitive,
+    {
+        let (min, max) = (self.min, self.max);
+        num::range_inclusive(min.0, max.0)
+            .flat_map(move |i| num::range_inclusive(min.1, max.1).map(move |j| Point(i, j)))
+    }
+}
+
+// An axis-aligned box of lattice points, with inclusive bounds on both ends.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub struct Cuboid<T> {
+    pub min: Point3<T>,
+    pub max: Point3<T>,
+}
+
+impl<T> Cuboid<T>
+where
+    T: Integer + Copy,
+{
+    // Box spanning both corners, in any order.
+    pub fn new(a: Point3<T>, b: Point3<T>) -> Self {
+        Self {
+            min: Point3(a.0.min(b.0), a.1.min(b.1), a.2.min(b.2)),
+            max: Point3(a.0.max(b.0), a.1.max(b.1), a.2.max(b.2)),
+        }
+    }
+
+    pub fn contains(&self, point: &Point3<T>) -> bool {
+        self.min.0 <= point.0
+            && point.0 <= self.max.0
+            && self.min.1 <= point.1
+            && point.1 <= self.max.1
+            && self.min.2 <= point.2
+            && point.2 <= self.max.2
+    }
+
+    pub fn contains_cuboid(&self, other: &Self) -> bool {
+        self.contains(&other.min) && self.contains(&other.max)
+    }
+
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        let min = Point3(
+            self.min.0.max(other.min.0),
+            self.min.1.max(other.min.1),
+            self.min.2.max(other.min.2),
+        );
+        let max = Point3(
+            self.max.0.min(other.max.0),
+            self.max.1.min(other.max.1),
+            self.max.2.min(other.max.2),
+        );
+        if min.0 <= max.0 && min.1 <= max.1 && min.2 <= max.2 {
+            Some(Self { min, max })
+        } else {
+            None
+        }
+    }
+
+    pub fn intersects(&self, other: &Self) -> bool {
+        self.intersection(other).is_some()
+    }
+
+    pub fn translate(&self, offset: Point3<T>) -> Self {
+        Self {
+            min: self.min + offset,
+            max: self.max + offset,
+        }
+    }
+
+    // Number of lattice points in the box.
+    pub fn volume(&self) -> T {
+        (self.max.0 - self.min.0 + T::one())
+            * (self.max.1 - self.min.1 + T::one())
+            * (self.max.2 - self.min.2 + T::one())
+    }
+
+    // Extent along the axis, as inclusive (low, high).
+    pub fn extent(&self, axis: Axis) -> (T, T) {
+        match axis {
+            Axis::X => (self.min.0, self.max.0),
+            Axis::Y => (self.min.1, self.max.1),
+            Axis::Z => (self.min.2, self.max.2),
+        }
+    }
+
+    // Flatten along the axis onto the plane of the other two, keeping them in (x, y, z) order.
+    pub fn project(&self, axis: Axis) -> Rect<T> {
+        let drop = |p: Point3<T>| match axis {
+            Axis::X => Point(p.1, p.2),
+            Axis::Y => Point(p.0, p.2),
+            Axis::Z => Point(p.0, p.1),
+        };
+        Rect {
+            min: drop(self.min),
+            max: drop(self.max),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use googletest::prelude::*;
+
+    #[googletest::test]
+    fn intersects_and_contains() {
+        let a = Cuboid::new(Point3(0, 0, 0), Point3(2, 2, 2));
+        let b = Cuboid::new(Point3(3, 1, 1), Point3(1, 5, 1));
+
+        expect_that!(
+            a.intersection(&b),
+            some(eq(Cuboid::new(Point3(1, 1, 1), Point3(2, 2, 1))))
+        );
+        expect_that!(a.intersects(&b.translate(Point3(0, 0, 2))), eq(false));
+        expect_that!(a.contains(&Point3(2, 0, 1)), eq(true));
+        expect_that!(a.contains_cuboid(&b), eq(false));
+        expect_that!(a.volume(), eq(27));
+    }
+
+    #[googletest::test]
+    fn projects_onto_plane() {
+        let brick = Cuboid::new(Point3(1_u32, 0, 5), Point3(1, 2, 7));
+
+        let footprint = brick.project(Axis::Z);
+        expect_that!(footprint, eq(Rect::new(Point(1, 0), Point(1, 2))));
+        expect_that!(footprint.area(), eq(3));
+        expect_that!(
+            footprint.points().collect::<Vec<_>>(),
+            eq(vec![Point(1, 0), Point(1, 1), Point(1, 2)])
+        );
+        expect_that!(brick.extent(Axis::Z), eq((5, 7)));
+    }
+}
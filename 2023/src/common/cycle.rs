@@ -0,0 +1,183 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+// Where an iterated state machine starts repeating: the state after `start` steps is the first
+// one seen again, `length` steps later.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cycle {
+    pub start: usize,
+    pub length: usize,
+}
+
+impl Cycle {
+    // The smallest number of steps that reaches the same state as `steps` steps.
+    pub fn equivalent_steps(&self, steps: usize) -> usize {
+        if steps < self.start {
+            return steps;
+        }
+        return self.start + (steps - self.start) % self.length;
+    }
+}
+
+// Floyd's tortoise and hare; stores only two states at a time.
+pub fn floyd<S, F>(initial: &S, mut step: F) -> Cycle
+where
+    S: Clone + Eq,
+    F: FnMut(&S) -> S,
+{
+    let mut tortoise = step(initial);
+    let mut hare = step(&tortoise);
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        hare = step(&hare);
+    }
+
+    let mut start = 0;
+    tortoise = initial.clone();
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+
+    let mut length = 1;
+    hare = step(&tortoise);
+    while tortoise != hare {
+        hare = step(&hare);
+        length += 1;
+    }
+    return Cycle { start, length };
+}
+
+// Brent's algorithm; stores only two states at a time and calls `step` less often than Floyd.
+pub fn brent<S, F>(initial: &S, mut step: F) -> Cycle
+where
+    S: Clone + Eq,
+    F: FnMut(&S) -> S,
+{
+    let mut power = 1;
+    let mut length = 1;
+    let mut tortoise = initial.clone();
+    let mut hare = step(initial);
+    while tortoise != hare {
+        if power == length {
+            tortoise = hare.clone();
+            power *= 2;
+            length = 0;
+        }
+        hare = step(&hare);
+        length += 1;
+    }
+
+    let mut start = 0;
+    tortoise = initial.clone();
+    hare = initial.clone();
+    for _ in 0..length {
+        hare = step(&hare);
+    }
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+    return Cycle { start, length };
+}
+
+// Every state visited up to and including the first repetition, found by hashing.
+#[derive(Debug, Clone)]
+pub struct History<S> {
+    pub cycle: Cycle,
+    // States after 0, 1, ..., start + length - 1 steps.
+    pub states: Vec<S>,
+}
+
+impl<S> History<S> {
+    pub fn state_after(&self, steps: usize) -> &S {
+        return &self.states[self.cycle.equivalent_steps(steps)];
+    }
+}
+
+// Calls `step` exactly once per distinct state, which suits expensive steps with side effects.
+pub fn find_with_history<S, F>(initial: S, mut step: F) -> History<S>
+where
+    S: Clone + Eq + Hash,
+    F: FnMut(&S) -> S,
+{
+    let mut seen = HashMap::from([(initial.clone(), 0)]);
+    let mut states = vec![initial];
+    loop {
+        let next = step(states.last().unwrap());
+        if let Some(&start) = seen.get(&next) {
+            let length = states.len() - start;
+            return History {
+                cycle: Cycle { start, length },
+                states,
+            };
+        }
+        seen.insert(next.clone(), states.len());
+        states.push(next);
+    }
+}
+
+// The state after `steps` steps, without running all of them.
+pub fn state_after<S, F>(initial: S, mut step: F, steps: usize) -> S
+where
+    S: Clone + Eq,
+    F: FnMut(&S) -> S,
+{
+    let cycle = brent(&initial, &mut step);
+    let mut state = initial;
+    for _ in 0..cycle.equivalent_steps(steps) {
+        state = step(&state);
+    }
+    return state;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use googletest::prelude::*;
+
+    // 0 -> 1 -> 2 -> 3 -> 4 -> 5 -> 6 -> 3 -> ...
+    fn step(n: &u32) -> u32 {
+        if *n < 3 {
+            n + 1
+        } else {
+            3 + (n - 2) % 4
+        }
+    }
+
+    #[googletest::test]
+    fn finds_cycle_start_and_length() {
+        let expected = Cycle {
+            start: 3,
+            length: 4,
+        };
+        expect_that!(floyd(&0, step), eq(expected));
+        expect_that!(brent(&0, step), eq(expected));
+        expect_that!(find_with_history(0, step).cycle, eq(expected));
+    }
+
+    #[googletest::test]
+    fn finds_cycle_starting_at_initial_state() {
+        let step = |n: &u32| (n + 1) % 5;
+        let expected = Cycle {
+            start: 0,
+            length: 5,
+        };
+        expect_that!(floyd(&0, step), eq(expected));
+        expect_that!(brent(&0, step), eq(expected));
+        expect_that!(find_with_history(0, step).cycle, eq(expected));
+    }
+
+    #[googletest::test]
+    fn extrapolates_state_after_many_steps() {
+        expect_that!(state_after(0, step, 2), eq(2));
+        expect_that!(state_after(0, step, 1_000_000_000), eq(4));
+        expect_that!(
+            *find_with_history(0, step).state_after(1_000_000_000),
+            eq(4)
+        );
+    }
+}
@@ -0,0 +1,23 @@
+use once_cell::sync::OnceCell;
+use std::fs;
+use std::path::PathBuf;
+
+static OUTPUT_DIR: OnceCell<PathBuf> = OnceCell::new();
+
+// Enable debug output, written into the given directory. Can only be set once.
+pub fn enable_output(dir: &str) {
+    let dir = PathBuf::from(dir);
+    fs::create_dir_all(&dir).expect("Cannot create debug output directory");
+    OUTPUT_DIR
+        .set(dir)
+        .expect("Debug output directory already set");
+}
+
+pub fn is_enabled() -> bool {
+    OUTPUT_DIR.get().is_some()
+}
+
+// Path for a debug file with the given name, or None if debug output is disabled.
+pub fn output_path(file_name: &str) -> Option<PathBuf> {
+    OUTPUT_DIR.get().map(|dir| dir.join(file_name))
+}
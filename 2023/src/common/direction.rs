@@ -1,3 +1,6 @@
+use std::fmt::Display;
+use std::str::FromStr;
+
 #[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
 pub enum Direction {
     North,
@@ -23,8 +26,9 @@ impl Direction {
         }
     }
 
-    pub fn reflect(&self, mirror: char) -> Self {
-        match mirror {
+    // Reflect off a '/' or '\\' mirror.
+    pub fn reflect(&self, mirror: char) -> Result<Self, DirectionError> {
+        Ok(match mirror {
             '\\' => match self {
                 Self::North => Self::West,
                 Self::West => Self::North,
@@ -37,8 +41,8 @@ impl Direction {
                 Self::South => Self::West,
                 Self::West => Self::South,
             },
-            c => panic!("Unexpected mirror character {c}"),
-        }
+            c => return Err(DirectionError::UnknownMirror(c)),
+        })
     }
 
     pub fn rotate(&self, degrees: i32) -> Self {
@@ -51,7 +55,313 @@ impl Direction {
             Self::South => 2,
             Self::West => 3,
         } + (degrees / 90);
-        let slice_idx = (slice_idx % 4) as usize;
+        let slice_idx = slice_idx.rem_euclid(4) as usize;
         DIRECTIONS_BY_ORIENTATION[slice_idx]
     }
+
+    pub fn turn(&self, turn: Turn) -> Self {
+        match turn {
+            Turn::Straight => *self,
+            Turn::Right => self.rotate(90),
+            Turn::Back => self.opposite(),
+            Turn::Left => self.rotate(-90),
+        }
+    }
+
+    // The turn that takes us from facing this direction to facing the other one.
+    pub fn turn_to(&self, other: Direction) -> Turn {
+        [Turn::Straight, Turn::Right, Turn::Back, Turn::Left]
+            .into_iter()
+            .find(|&turn| self.turn(turn) == other)
+            .unwrap()
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum DirectionError {
+    UnknownDirection(String),
+    UnknownMirror(char),
+    UnknownTurn(char),
+}
+
+impl Display for DirectionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnknownDirection(d) => write!(f, "Unknown direction {d:?}"),
+            Self::UnknownMirror(c) => write!(f, "Unknown mirror character {c:?}"),
+            Self::UnknownTurn(c) => write!(f, "Unknown turn {c:?}"),
+        }
+    }
+}
+
+// Accepts arrows (^v<>), compass letters (NSEW), relative letters (UDLR), and digits counting
+// clockwise from east (0 = East, 1 = South, 2 = West, 3 = North).
+impl TryFrom<char> for Direction {
+    type Error = DirectionError;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        match c {
+            '^' | 'N' | 'U' | '3' => Ok(Self::North),
+            'v' | 'S' | 'D' | '1' => Ok(Self::South),
+            '>' | 'E' | 'R' | '0' => Ok(Self::East),
+            '<' | 'W' | 'L' | '2' => Ok(Self::West),
+            c => Err(DirectionError::UnknownDirection(c.to_string())),
+        }
+    }
+}
+
+// Accepts anything `TryFrom<char>` does, as well as full names like "north" or "up".
+impl FromStr for Direction {
+    type Err = DirectionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        if let (Some(c), None) = (chars.next(), chars.next()) {
+            return Self::try_from(c);
+        }
+        match s.to_ascii_lowercase().as_str() {
+            "north" | "up" => Ok(Self::North),
+            "south" | "down" => Ok(Self::South),
+            "east" | "right" => Ok(Self::East),
+            "west" | "left" => Ok(Self::West),
+            _ => Err(DirectionError::UnknownDirection(s.to_string())),
+        }
+    }
+}
+
+// A turn relative to the current heading.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub enum Turn {
+    Straight,
+    Left,
+    Right,
+    Back,
+}
+
+// Accepts 'S' (straight), 'L', 'R' and 'B' (back).
+impl TryFrom<char> for Turn {
+    type Error = DirectionError;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        match c {
+            'S' => Ok(Self::Straight),
+            'L' => Ok(Self::Left),
+            'R' => Ok(Self::Right),
+            'B' => Ok(Self::Back),
+            c => Err(DirectionError::UnknownTurn(c)),
+        }
+    }
+}
+
+// Compass directions including diagonals, in clockwise order starting from north.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub enum Direction8 {
+    North,
+    NorthEast,
+    East,
+    SouthEast,
+    South,
+    SouthWest,
+    West,
+    NorthWest,
+}
+
+impl Direction8 {
+    pub const ALL: [Direction8; 8] = [
+        Self::North,
+        Self::NorthEast,
+        Self::East,
+        Self::SouthEast,
+        Self::South,
+        Self::SouthWest,
+        Self::West,
+        Self::NorthWest,
+    ];
+
+    pub const ORTHOGONAL: [Direction8; 4] = [Self::North, Self::East, Self::South, Self::West];
+
+    pub const DIAGONAL: [Direction8; 4] = [
+        Self::NorthEast,
+        Self::SouthEast,
+        Self::SouthWest,
+        Self::NorthWest,
+    ];
+
+    fn index(&self) -> i32 {
+        Self::ALL.iter().position(|d| d == self).unwrap() as i32
+    }
+
+    pub fn is_diagonal(&self) -> bool {
+        self.index() % 2 == 1
+    }
+
+    pub fn opposite(&self) -> Self {
+        self.rotate_steps(4)
+    }
+
+    // Rotate clockwise by the given number of 45 degree steps; negative steps rotate counterclockwise.
+    pub fn rotate_steps(&self, steps: i32) -> Self {
+        Self::ALL[(self.index() + steps).rem_euclid(8) as usize]
+    }
+
+    // Rotate clockwise by degrees, or None if it's not a multiple of 45.
+    pub fn rotate(&self, degrees: i32) -> Option<Self> {
+        if degrees % 45 != 0 {
+            return None;
+        }
+        Some(self.rotate_steps(degrees / 45))
+    }
+
+    // (row, column) offset of a single step, with rows increasing southwards.
+    pub fn offset(&self) -> (i32, i32) {
+        match self {
+            Self::North => (-1, 0),
+            Self::NorthEast => (-1, 1),
+            Self::East => (0, 1),
+            Self::SouthEast => (1, 1),
+            Self::South => (1, 0),
+            Self::SouthWest => (1, -1),
+            Self::West => (0, -1),
+            Self::NorthWest => (-1, -1),
+        }
+    }
+}
+
+impl From<Direction> for Direction8 {
+    fn from(direction: Direction) -> Self {
+        match direction {
+            Direction::North => Self::North,
+            Direction::East => Self::East,
+            Direction::South => Self::South,
+            Direction::West => Self::West,
+        }
+    }
+}
+
+impl TryFrom<Direction8> for Direction {
+    type Error = Direction8;
+
+    // Fails for diagonal directions, returning them as the error.
+    fn try_from(direction: Direction8) -> Result<Self, Self::Error> {
+        match direction {
+            Direction8::North => Ok(Self::North),
+            Direction8::East => Ok(Self::East),
+            Direction8::South => Ok(Self::South),
+            Direction8::West => Ok(Self::West),
+            diagonal => Err(diagonal),
+        }
+    }
+}
+
+// Directions on a hex grid with flat-topped hexes, in clockwise order starting from north.
+// Paired with `point::Axial` coordinates.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub enum HexDirection {
+    North,
+    NorthEast,
+    SouthEast,
+    South,
+    SouthWest,
+    NorthWest,
+}
+
+impl HexDirection {
+    pub const ALL: [HexDirection; 6] = [
+        Self::North,
+        Self::NorthEast,
+        Self::SouthEast,
+        Self::South,
+        Self::SouthWest,
+        Self::NorthWest,
+    ];
+
+    fn index(&self) -> i32 {
+        Self::ALL.iter().position(|d| d == self).unwrap() as i32
+    }
+
+    pub fn opposite(&self) -> Self {
+        self.rotate_steps(3)
+    }
+
+    // Rotate clockwise by the given number of 60 degree steps; negative steps rotate counterclockwise.
+    pub fn rotate_steps(&self, steps: i32) -> Self {
+        Self::ALL[(self.index() + steps).rem_euclid(6) as usize]
+    }
+
+    // (q, r) axial offset of a single step.
+    pub fn offset(&self) -> (i32, i32) {
+        match self {
+            Self::North => (0, -1),
+            Self::NorthEast => (1, -1),
+            Self::SouthEast => (1, 0),
+            Self::South => (0, 1),
+            Self::SouthWest => (-1, 1),
+            Self::NorthWest => (-1, 0),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use googletest::prelude::*;
+
+    #[googletest::test]
+    fn rotates_directions() {
+        expect_that!(Direction::North.rotate(-90), eq(Direction::West));
+        expect_that!(
+            Direction8::North.rotate(45),
+            some(eq(Direction8::NorthEast))
+        );
+        expect_that!(
+            Direction8::NorthEast.rotate(-135),
+            some(eq(Direction8::West))
+        );
+        expect_that!(Direction8::North.rotate(30), none());
+        expect_that!(Direction8::SouthWest.opposite(), eq(Direction8::NorthEast));
+        expect_that!(
+            HexDirection::NorthWest.rotate_steps(2),
+            eq(HexDirection::NorthEast)
+        );
+        expect_that!(HexDirection::North.opposite(), eq(HexDirection::South));
+    }
+
+    #[googletest::test]
+    fn converts_between_four_and_eight_directions() {
+        expect_that!(Direction8::from(Direction::West), eq(Direction8::West));
+        expect_that!(
+            Direction::try_from(Direction8::South),
+            ok(eq(Direction::South))
+        );
+        expect_that!(Direction::try_from(Direction8::NorthWest), err(anything()));
+        expect_that!(Direction8::NorthWest.is_diagonal(), eq(true));
+    }
+
+    #[googletest::test]
+    fn parses_directions() {
+        expect_that!(Direction::try_from('^'), ok(eq(Direction::North)));
+        expect_that!(Direction::try_from('W'), ok(eq(Direction::West)));
+        expect_that!(Direction::try_from('D'), ok(eq(Direction::South)));
+        expect_that!(Direction::try_from('0'), ok(eq(Direction::East)));
+        expect_that!("Left".parse::<Direction>(), ok(eq(Direction::West)));
+        expect_that!(
+            "x".parse::<Direction>(),
+            err(eq(DirectionError::UnknownDirection("x".to_string())))
+        );
+        expect_that!(
+            Direction::North.reflect('|'),
+            err(eq(DirectionError::UnknownMirror('|')))
+        );
+        expect_that!(Direction::North.reflect('/'), ok(eq(Direction::East)));
+    }
+
+    #[googletest::test]
+    fn turns_relative_to_heading() {
+        expect_that!(Direction::North.turn(Turn::Left), eq(Direction::West));
+        expect_that!(Direction::West.turn(Turn::Right), eq(Direction::North));
+        expect_that!(Direction::East.turn(Turn::Back), eq(Direction::West));
+        expect_that!(Direction::South.turn_to(Direction::East), eq(Turn::Left));
+        expect_that!(Turn::try_from('B'), ok(eq(Turn::Back)));
+        expect_that!(Turn::try_from('X'), err(anything()));
+    }
 }
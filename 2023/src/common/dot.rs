@@ -0,0 +1,154 @@
+use crate::common::graph::{Graph, GraphKind, NodeId, NodeTable};
+use crate::common::intern::Symbol;
+use std::fmt::{self, Display};
+
+type Attributes = Vec<(String, String)>;
+
+// Graphviz DOT output. Render with e.g. `dot -Tsvg graph.dot -o graph.svg`.
+#[derive(Debug, Clone)]
+pub struct DotGraph {
+    kind: GraphKind,
+    name: String,
+    // Applied to every node unless overridden, e.g. ("shape", "box").
+    node_defaults: Attributes,
+    nodes: Vec<(String, Attributes)>,
+    edges: Vec<(String, String, Attributes)>,
+}
+
+impl DotGraph {
+    pub fn new(name: &str, kind: GraphKind) -> Self {
+        Self {
+            kind,
+            name: name.to_string(),
+            node_defaults: vec![],
+            nodes: vec![],
+            edges: vec![],
+        }
+    }
+
+    pub fn with_node_defaults(mut self, attributes: &[(&str, &str)]) -> Self {
+        self.node_defaults = to_owned(attributes);
+        self
+    }
+
+    // Nodes only need declaring to give them attributes; edges create their endpoints.
+    // Declaring a node again adds to its attributes.
+    pub fn node(&mut self, id: &str, attributes: &[(&str, &str)]) {
+        match self.nodes.iter_mut().find(|(existing, _)| existing == id) {
+            Some((_, existing_attributes)) => existing_attributes.extend(to_owned(attributes)),
+            None => self.nodes.push((id.to_string(), to_owned(attributes))),
+        }
+    }
+
+    pub fn edge(&mut self, from: &str, to: &str, attributes: &[(&str, &str)]) {
+        self.edges
+            .push((from.to_string(), to.to_string(), to_owned(attributes)));
+    }
+}
+
+fn to_owned(attributes: &[(&str, &str)]) -> Attributes {
+    attributes
+        .iter()
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect()
+}
+
+// Quotes an ID so any name is valid DOT.
+fn quote(id: &str) -> String {
+    format!("\"{}\"", id.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn format_attributes(attributes: &[(String, String)]) -> String {
+    if attributes.is_empty() {
+        return String::new();
+    }
+    let attributes = attributes
+        .iter()
+        .map(|(key, value)| format!("{key}={}", quote(value)))
+        .collect::<Vec<_>>()
+        .join(", ");
+    return format!(" [{attributes}]");
+}
+
+impl Display for DotGraph {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (keyword, edge_op) = match self.kind {
+            GraphKind::Directed => ("digraph", "->"),
+            GraphKind::Undirected => ("graph", "--"),
+        };
+        writeln!(f, "{keyword} {} {{", quote(&self.name))?;
+        if !self.node_defaults.is_empty() {
+            writeln!(f, "  node{};", format_attributes(&self.node_defaults))?;
+        }
+        for (id, attributes) in &self.nodes {
+            writeln!(f, "  {}{};", quote(id), format_attributes(attributes))?;
+        }
+        for (from, to, attributes) in &self.edges {
+            writeln!(
+                f,
+                "  {} {edge_op} {}{};",
+                quote(from),
+                quote(to),
+                format_attributes(attributes)
+            )?;
+        }
+        writeln!(f, "}}")
+    }
+}
+
+impl Graph {
+    // Plain DOT rendering, naming each node from `nodes`. Undirected edges are written once.
+    pub fn to_dot(&self, name: &str, nodes: &NodeTable) -> DotGraph {
+        let mut dot = DotGraph::new(name, self.kind());
+        for (symbol, node_name) in nodes.iter() {
+            let id = NodeId::from(symbol);
+            dot.node(node_name, &[]);
+            for &to in self.successors(id) {
+                if self.kind() == GraphKind::Directed || id <= to {
+                    dot.edge(node_name, nodes.name(Symbol::from_index(to)), &[]);
+                }
+            }
+        }
+        dot
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use googletest::prelude::*;
+
+    #[googletest::test]
+    fn writes_attributes_and_escapes_ids() {
+        let mut dot =
+            DotGraph::new("modules", GraphKind::Directed).with_node_defaults(&[("shape", "box")]);
+        dot.node("&zh", &[("shape", "invhouse")]);
+        dot.node("&zh", &[("color", "red")]);
+        dot.edge("say \"hi\"", "&zh", &[("label", "a<2006")]);
+        expect_that!(
+            dot.to_string(),
+            eq("digraph \"modules\" {
+  node [shape=\"box\"];
+  \"&zh\" [shape=\"invhouse\", color=\"red\"];
+  \"say \\\"hi\\\"\" -> \"&zh\" [label=\"a<2006\"];
+}
+")
+        );
+    }
+
+    #[googletest::test]
+    fn converts_undirected_graphs() {
+        let mut nodes = NodeTable::new();
+        let mut graph = Graph::undirected(0);
+        graph.add_edge(nodes.intern("a").into(), nodes.intern("b").into());
+        expect_that!(
+            graph.to_dot("g", &nodes).to_string(),
+            eq("graph \"g\" {
+  \"a\";
+  \"b\";
+  \"a\" -- \"b\";
+}
+")
+        );
+    }
+}
@@ -0,0 +1,520 @@
+use crate::common::point::{Coordinates, Point, Point3};
+use num::{BigInt, BigRational, Integer, One, Signed, Zero};
+
+// Polygon helpers over integer vertices, given in order (either orientation) without repeating
+// the first vertex at the end. Arithmetic is overflow-checked and returns None on overflow.
+
+// Twice the signed area from the shoelace formula; positive when the vertices go clockwise
+// in (row, column) space.
+fn doubled_signed_area(vertices: &[Coordinates]) -> Option<i64> {
+    let mut doubled_area = 0_i64;
+    for (a, b) in edges(vertices) {
+        let cross = a.1.checked_mul(b.0)?.checked_sub(a.0.checked_mul(b.1)?)?;
+        doubled_area = doubled_area.checked_add(cross)?;
+    }
+    Some(doubled_area)
+}
+
+fn edges(vertices: &[Coordinates]) -> impl Iterator<Item = (Coordinates, Coordinates)> + '_ {
+    vertices
+        .iter()
+        .zip(vertices.iter().cycle().skip(1))
+        .map(|(&a, &b)| (a, b))
+}
+
+// Twice the enclosed area. Doubled so it stays an integer for any lattice polygon.
+pub fn doubled_area(vertices: &[Coordinates]) -> Option<i64> {
+    doubled_signed_area(vertices)?.checked_abs()
+}
+
+// Number of lattice points on the polygon's edges.
+pub fn boundary_points(vertices: &[Coordinates]) -> Option<i64> {
+    let mut num_points = 0_i64;
+    for (a, b) in edges(vertices) {
+        let dx = b.0.checked_sub(a.0)?.checked_abs()?;
+        let dy = b.1.checked_sub(a.1)?.checked_abs()?;
+        num_points = num_points.checked_add(dx.gcd(&dy))?;
+    }
+    Some(num_points)
+}
+
+// Euclidean length of the polygon's edges.
+pub fn perimeter(vertices: &[Coordinates]) -> Option<f64> {
+    let mut length = 0.0;
+    for (a, b) in edges(vertices) {
+        let dx = b.0.checked_sub(a.0)?;
+        let dy = b.1.checked_sub(a.1)?;
+        length += (dx as f64).hypot(dy as f64);
+    }
+    Some(length)
+}
+
+// Number of lattice points strictly inside the polygon, by Pick's theorem: A = I + B/2 - 1.
+pub fn interior_points(vertices: &[Coordinates]) -> Option<i64> {
+    let doubled_area = doubled_area(vertices)?;
+    let boundary = boundary_points(vertices)?;
+    Some(doubled_area.checked_sub(boundary)?.checked_add(2)? / 2)
+}
+
+// Number of lattice points inside or on the polygon, e.g. the cells covered by a dug-out trench.
+pub fn enclosed_points(vertices: &[Coordinates]) -> Option<i64> {
+    interior_points(vertices)?.checked_add(boundary_points(vertices)?)
+}
+
+// Widen to i128 so cross products can't overflow.
+fn widen(point: Coordinates) -> Point<i128> {
+    Point(point.0 as i128, point.1 as i128)
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PointLocation {
+    Inside,
+    Outside,
+    OnBoundary,
+}
+
+// Locate a point relative to the polygon by casting a ray in the +column direction.
+pub fn locate_point(vertices: &[Coordinates], point: &Coordinates) -> PointLocation {
+    let mut is_inside = false;
+    for (a, b) in edges(vertices) {
+        let (a, b, p) = (widen(a), widen(b), widen(*point));
+        let cross = (b.0 - a.0) * (p.1 - a.1) - (b.1 - a.1) * (p.0 - a.0);
+        if cross == 0
+            && a.0.min(b.0) <= p.0
+            && p.0 <= a.0.max(b.0)
+            && a.1.min(b.1) <= p.1
+            && p.1 <= a.1.max(b.1)
+        {
+            return PointLocation::OnBoundary;
+        }
+
+        // Count edges crossing the point's row, treating each edge as half-open in rows.
+        if (a.0 > p.0) != (b.0 > p.0) {
+            // The crossing is to the right of the point if the point is on the left of the
+            // edge when it's oriented downwards.
+            let is_right_of_point = if b.0 > a.0 { cross < 0 } else { cross > 0 };
+            if is_right_of_point {
+                is_inside = !is_inside;
+            }
+        }
+    }
+
+    if is_inside {
+        PointLocation::Inside
+    } else {
+        PointLocation::Outside
+    }
+}
+
+// Which part of a parametric line origin + t * direction is included.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Extent {
+    // Any t.
+    Line,
+    // t >= 0.
+    Ray,
+    // 0 <= t <= 1.
+    Segment,
+}
+
+impl Extent {
+    // Inclusive (low, high) bounds on t, where None is unbounded.
+    fn bounds(&self) -> (Option<BigRational>, Option<BigRational>) {
+        match self {
+            Self::Line => (None, None),
+            Self::Ray => (Some(BigRational::zero()), None),
+            Self::Segment => (Some(BigRational::zero()), Some(BigRational::one())),
+        }
+    }
+
+    fn contains(&self, t: &BigRational) -> bool {
+        let (low, high) = self.bounds();
+        low.is_none_or(|low| &low <= t) && high.is_none_or(|high| t <= &high)
+    }
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum Intersection<const N: usize> {
+    // Meet at exactly one point.
+    Point([BigRational; N]),
+    // Not parallel, but don't meet within their extents (or are skew in 3D).
+    Disjoint,
+    // Parallel and on different lines.
+    Parallel,
+    // On the same line, sharing more than one point if `overlapping`.
+    Colinear { overlapping: bool },
+}
+
+// A line, ray or segment in N dimensions with integer coordinates. Intersections are computed
+// exactly with rationals. A zero direction, e.g. a zero-length segment, is a single point.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Line<const N: usize> {
+    pub origin: [i64; N],
+    pub direction: [i64; N],
+    pub extent: Extent,
+}
+
+pub type Line2 = Line<2>;
+pub type Line3 = Line<3>;
+
+impl Line2 {
+    // None if the offset from start to end overflows.
+    pub fn segment(start: Coordinates, end: Coordinates) -> Option<Self> {
+        Some(Self::new(
+            [start.0, start.1],
+            [end.0.checked_sub(start.0)?, end.1.checked_sub(start.1)?],
+            Extent::Segment,
+        ))
+    }
+
+    pub fn ray(origin: Coordinates, direction: Coordinates) -> Self {
+        Self::new(
+            [origin.0, origin.1],
+            [direction.0, direction.1],
+            Extent::Ray,
+        )
+    }
+
+    pub fn infinite(origin: Coordinates, direction: Coordinates) -> Self {
+        Self::new(
+            [origin.0, origin.1],
+            [direction.0, direction.1],
+            Extent::Line,
+        )
+    }
+}
+
+impl Line3 {
+    // None if the offset from start to end overflows.
+    pub fn segment(start: Point3<i64>, end: Point3<i64>) -> Option<Self> {
+        Some(Self::new(
+            [start.0, start.1, start.2],
+            [
+                end.0.checked_sub(start.0)?,
+                end.1.checked_sub(start.1)?,
+                end.2.checked_sub(start.2)?,
+            ],
+            Extent::Segment,
+        ))
+    }
+
+    pub fn ray(origin: Point3<i64>, direction: Point3<i64>) -> Self {
+        Self::new(
+            [origin.0, origin.1, origin.2],
+            [direction.0, direction.1, direction.2],
+            Extent::Ray,
+        )
+    }
+
+    pub fn infinite(origin: Point3<i64>, direction: Point3<i64>) -> Self {
+        Self::new(
+            [origin.0, origin.1, origin.2],
+            [direction.0, direction.1, direction.2],
+            Extent::Line,
+        )
+    }
+}
+
+fn dot<const N: usize>(a: &[BigInt; N], b: &[BigInt; N]) -> BigInt {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+impl<const N: usize> Line<N> {
+    pub fn new(origin: [i64; N], direction: [i64; N], extent: Extent) -> Self {
+        Self {
+            origin,
+            direction,
+            extent,
+        }
+    }
+
+    fn origin_big(&self) -> [BigInt; N] {
+        self.origin.map(BigInt::from)
+    }
+
+    fn direction_big(&self) -> [BigInt; N] {
+        self.direction.map(BigInt::from)
+    }
+
+    // Point at parameter t.
+    pub fn at(&self, t: &BigRational) -> [BigRational; N] {
+        let (origin, direction) = (self.origin_big(), self.direction_big());
+        std::array::from_fn(|i| {
+            BigRational::from(origin[i].clone()) + t * BigRational::from(direction[i].clone())
+        })
+    }
+
+    fn is_point(&self) -> bool {
+        self.direction.iter().all(|&d| d == 0)
+    }
+
+    // Parameter t at which we pass through `point`, if we do.
+    fn parameter_of(&self, point: &[BigRational; N]) -> Option<BigRational> {
+        let t = if self.is_point() {
+            BigRational::zero()
+        } else {
+            let direction = self.direction_big();
+            let offset: [BigRational; N] = std::array::from_fn(|i| {
+                &point[i] - BigRational::from(BigInt::from(self.origin[i]))
+            });
+            let along: BigRational = offset
+                .iter()
+                .zip(&direction)
+                .map(|(o, d)| o * BigRational::from(d.clone()))
+                .sum();
+            along / BigRational::from(dot(&direction, &direction))
+        };
+        (self.extent.contains(&t) && &self.at(&t) == point).then_some(t)
+    }
+
+    pub fn intersect(&self, other: &Self) -> Intersection<N> {
+        // A degenerate line meets the other only if it lies on it.
+        for (point, line) in [(self, other), (other, self)] {
+            if point.is_point() {
+                let at = point.at(&BigRational::zero());
+                return match line.parameter_of(&at) {
+                    Some(_) => Intersection::Point(at),
+                    None => Intersection::Disjoint,
+                };
+            }
+        }
+
+        let (o1, d1) = (self.origin_big(), self.direction_big());
+        let (o2, d2) = (other.origin_big(), other.direction_big());
+        let w: [BigInt; N] = std::array::from_fn(|i| &o2[i] - &o1[i]);
+
+        // Least squares for o1 + t * d1 = o2 + s * d2:
+        //   t (d1.d1) - s (d1.d2) = w.d1
+        //   t (d1.d2) - s (d2.d2) = w.d2
+        let (d11, d12, d22) = (dot(&d1, &d1), dot(&d1, &d2), dot(&d2, &d2));
+        let (wd1, wd2) = (dot(&w, &d1), dot(&w, &d2));
+        let determinant = &d12 * &d12 - &d11 * &d22;
+
+        if determinant.is_zero() {
+            // Parallel: colinear if w is parallel to d1 too, i.e. |w.d1|^2 = (w.w)(d1.d1).
+            if &wd1 * &wd1 != dot(&w, &w) * &d11 {
+                return Intersection::Parallel;
+            }
+            return self.intersect_colinear(other, &wd1, &d11, &d12);
+        }
+
+        let t = BigRational::new(&d12 * &wd2 - &d22 * &wd1, determinant.clone());
+        let s = BigRational::new(&d11 * &wd2 - &d12 * &wd1, determinant);
+        let point = self.at(&t);
+        if point != other.at(&s) {
+            // Skew lines in 3D.
+            return Intersection::Disjoint;
+        }
+        if self.extent.contains(&t) && other.extent.contains(&s) {
+            Intersection::Point(point)
+        } else {
+            Intersection::Disjoint
+        }
+    }
+
+    fn intersect_colinear(
+        &self,
+        other: &Self,
+        wd1: &BigInt,
+        d11: &BigInt,
+        d12: &BigInt,
+    ) -> Intersection<N> {
+        // Map the other's parameter s onto our t: t = offset + s * scale.
+        let offset = BigRational::new(wd1.clone(), d11.clone());
+        let scale = BigRational::new(d12.clone(), d11.clone());
+        let to_t = |s: Option<BigRational>| s.map(|s| &offset + s * &scale);
+        let (low, high) = other.extent.bounds();
+        let (other_low, other_high) = if scale.is_negative() {
+            (to_t(high), to_t(low))
+        } else {
+            (to_t(low), to_t(high))
+        };
+
+        let (self_low, self_high) = self.extent.bounds();
+        let low = match (self_low, other_low) {
+            (Some(a), Some(b)) => Some(a.max(b)),
+            (a, b) => a.or(b),
+        };
+        let high = match (self_high, other_high) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+
+        match (low, high) {
+            (Some(low), Some(high)) if low > high => Intersection::Colinear { overlapping: false },
+            (Some(low), Some(high)) if low == high => Intersection::Point(self.at(&low)),
+            _ => Intersection::Colinear { overlapping: true },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use googletest::prelude::*;
+
+    fn square() -> Vec<Coordinates> {
+        vec![Point(0, 0), Point(0, 4), Point(4, 4), Point(4, 0)]
+    }
+
+    #[googletest::test]
+    fn computes_area_and_lattice_points() {
+        expect_that!(doubled_area(&square()), some(eq(32)));
+        expect_that!(boundary_points(&square()), some(eq(16)));
+        expect_that!(interior_points(&square()), some(eq(9)));
+        expect_that!(enclosed_points(&square()), some(eq(25)));
+        expect_that!(perimeter(&square()), some(eq(16.0)));
+
+        let triangle = vec![Point(0, 0), Point(2, 4), Point(0, 4)];
+        expect_that!(doubled_area(&triangle), some(eq(8)));
+        expect_that!(boundary_points(&triangle), some(eq(8)));
+        expect_that!(interior_points(&triangle), some(eq(1)));
+    }
+
+    #[googletest::test]
+    fn reports_overflow() {
+        let huge = vec![Point(0, 0), Point(0, i64::MAX), Point(i64::MAX, i64::MAX)];
+        expect_that!(doubled_area(&huge), none());
+
+        let wide = vec![Point(0, i64::MIN), Point(0, i64::MAX), Point(1, 0)];
+        expect_that!(perimeter(&wide), none());
+    }
+
+    #[googletest::test]
+    fn locates_points() {
+        let l_shape = vec![
+            Point(0, 0),
+            Point(0, 2),
+            Point(2, 2),
+            Point(2, 4),
+            Point(4, 4),
+            Point(4, 0),
+        ];
+        expect_that!(
+            locate_point(&l_shape, &Point(3, 1)),
+            eq(PointLocation::Inside)
+        );
+        expect_that!(
+            locate_point(&l_shape, &Point(1, 3)),
+            eq(PointLocation::Outside)
+        );
+        expect_that!(
+            locate_point(&l_shape, &Point(2, 3)),
+            eq(PointLocation::OnBoundary)
+        );
+        expect_that!(
+            locate_point(&l_shape, &Point(0, 1)),
+            eq(PointLocation::OnBoundary)
+        );
+        expect_that!(
+            locate_point(&l_shape, &Point(1, 5)),
+            eq(PointLocation::Outside)
+        );
+    }
+
+    fn rational_point<const N: usize>(values: [(i64, i64); N]) -> [BigRational; N] {
+        values.map(|(n, d)| BigRational::new(n.into(), d.into()))
+    }
+
+    #[googletest::test]
+    fn intersects_segments_and_rays_in_2d() {
+        let a = Line2::segment(Point(0, 0), Point(4, 4)).unwrap();
+        expect_that!(
+            a.intersect(&Line2::segment(Point(0, 3), Point(3, 0)).unwrap()),
+            eq(Intersection::Point(rational_point([(3, 2), (3, 2)])))
+        );
+        expect_that!(
+            a.intersect(&Line2::segment(Point(0, 9), Point(1, 8)).unwrap()),
+            eq(Intersection::Disjoint)
+        );
+        expect_that!(
+            a.intersect(&Line2::ray(Point(0, 9), Point(1, -1))),
+            eq(Intersection::Disjoint)
+        );
+        expect_that!(
+            Line2::ray(Point(0, 9), Point(1, -1))
+                .intersect(&Line2::infinite(Point(0, 0), Point(1, 1))),
+            eq(Intersection::Point(rational_point([(9, 2), (9, 2)])))
+        );
+        expect_that!(
+            a.intersect(&Line2::segment(Point(1, 0), Point(5, 4)).unwrap()),
+            eq(Intersection::Parallel)
+        );
+    }
+
+    #[googletest::test]
+    fn classifies_colinear_segments() {
+        let a = Line2::segment(Point(0, 0), Point(2, 2)).unwrap();
+        expect_that!(
+            a.intersect(&Line2::segment(Point(3, 3), Point(1, 1)).unwrap()),
+            eq(Intersection::Colinear { overlapping: true })
+        );
+        expect_that!(
+            a.intersect(&Line2::segment(Point(2, 2), Point(5, 5)).unwrap()),
+            eq(Intersection::Point(rational_point([(2, 1), (2, 1)])))
+        );
+        expect_that!(
+            a.intersect(&Line2::segment(Point(3, 3), Point(5, 5)).unwrap()),
+            eq(Intersection::Colinear { overlapping: false })
+        );
+        expect_that!(
+            a.intersect(&Line2::ray(Point(3, 3), Point(-1, -1))),
+            eq(Intersection::Colinear { overlapping: true })
+        );
+    }
+
+    #[googletest::test]
+    fn treats_zero_length_segments_as_points() {
+        let a = Line2::segment(Point(0, 0), Point(4, 4)).unwrap();
+        let on_a = Line2::segment(Point(1, 1), Point(1, 1)).unwrap();
+        expect_that!(
+            a.intersect(&on_a),
+            eq(Intersection::Point(rational_point([(1, 1), (1, 1)])))
+        );
+        expect_that!(
+            on_a.intersect(&Line2::ray(Point(2, 2), Point(1, 1))),
+            eq(Intersection::Disjoint)
+        );
+        expect_that!(
+            Line2::ray(Point(5, 0), Point(0, 0))
+                .intersect(&Line2::infinite(Point(0, 0), Point(1, 0))),
+            eq(Intersection::Point(rational_point([(5, 1), (0, 1)])))
+        );
+        expect_that!(Line2::segment(Point(0, i64::MIN), Point(0, 1)), none());
+    }
+
+    #[googletest::test]
+    fn intersects_lines_in_3d() {
+        let a = Line3::infinite(Point3(0, 0, 0), Point3(1, 1, 1));
+        expect_that!(
+            a.intersect(&Line3::infinite(Point3(2, 0, 2), Point3(0, 1, 0))),
+            eq(Intersection::Point(rational_point([
+                (2, 1),
+                (2, 1),
+                (2, 1)
+            ])))
+        );
+        expect_that!(
+            a.intersect(&Line3::infinite(Point3(1, 0, 0), Point3(0, 0, 1))),
+            eq(Intersection::Disjoint)
+        );
+        expect_that!(
+            a.intersect(&Line3::infinite(Point3(1, 0, 0), Point3(2, 2, 2))),
+            eq(Intersection::Parallel)
+        );
+    }
+
+    #[googletest::test]
+    fn intersects_large_rays_exactly() {
+        // Hailstone-sized coordinates, where floating point loses precision.
+        let a = Line2::ray(Point(200_000_000_000_001, 0), Point(-3, 1));
+        let b = Line2::ray(Point(0, 0), Point(7, 2));
+        expect_that!(
+            a.intersect(&b),
+            eq(Intersection::Point(rational_point([
+                (1_400_000_000_000_007, 13),
+                (400_000_000_000_002, 13)
+            ])))
+        );
+    }
+}
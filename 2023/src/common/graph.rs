@@ -0,0 +1,449 @@
+use crate::common::intern::Symbol;
+use std::collections::{HashMap, VecDeque};
+
+pub use crate::common::intern::Interner as NodeTable;
+
+pub type NodeId = usize;
+
+// A graph built alongside a `NodeTable` uses node i for symbol i; go back with
+// `Symbol::from_index`.
+impl From<Symbol> for NodeId {
+    fn from(symbol: Symbol) -> Self {
+        symbol.index()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GraphKind {
+    Directed,
+    // Every edge is stored in both directions.
+    Undirected,
+}
+
+// Adjacency lists over nodes 0..len(); nodes are added as edges mention them.
+#[derive(Debug, Clone)]
+pub struct Graph {
+    kind: GraphKind,
+    successors: Vec<Vec<NodeId>>,
+    predecessors: Vec<Vec<NodeId>>,
+}
+
+impl Graph {
+    pub fn directed(num_nodes: usize) -> Self {
+        Self::with_kind(GraphKind::Directed, num_nodes)
+    }
+
+    pub fn undirected(num_nodes: usize) -> Self {
+        Self::with_kind(GraphKind::Undirected, num_nodes)
+    }
+
+    fn with_kind(kind: GraphKind, num_nodes: usize) -> Self {
+        Self {
+            kind,
+            successors: vec![vec![]; num_nodes],
+            predecessors: vec![vec![]; num_nodes],
+        }
+    }
+
+    pub fn kind(&self) -> GraphKind {
+        self.kind
+    }
+
+    pub fn len(&self) -> usize {
+        self.successors.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.successors.is_empty()
+    }
+
+    pub fn add_node(&mut self) -> NodeId {
+        self.successors.push(vec![]);
+        self.predecessors.push(vec![]);
+        return self.successors.len() - 1;
+    }
+
+    pub fn add_edge(&mut self, from: NodeId, to: NodeId) {
+        while self.len() <= from.max(to) {
+            self.add_node();
+        }
+        self.successors[from].push(to);
+        self.predecessors[to].push(from);
+        if self.kind == GraphKind::Undirected && from != to {
+            self.successors[to].push(from);
+            self.predecessors[from].push(to);
+        }
+    }
+
+    // Nodes with an edge from `node`; for undirected graphs, all of its neighbors.
+    pub fn successors(&self, node: NodeId) -> &[NodeId] {
+        &self.successors[node]
+    }
+
+    pub fn predecessors(&self, node: NodeId) -> &[NodeId] {
+        &self.predecessors[node]
+    }
+
+    // Every edge as (from, to); undirected edges appear once in each direction.
+    pub fn edges(&self) -> impl Iterator<Item = (NodeId, NodeId)> + '_ {
+        self.successors
+            .iter()
+            .enumerate()
+            .flat_map(|(from, tos)| tos.iter().map(move |&to| (from, to)))
+    }
+
+    // Nodes ordered so every edge points forwards, or None if there's a cycle.
+    pub fn topological_sort(&self) -> Option<Vec<NodeId>> {
+        let mut in_degrees: Vec<usize> = self.predecessors.iter().map(Vec::len).collect();
+        let mut ready: VecDeque<NodeId> = (0..self.len()).filter(|&n| in_degrees[n] == 0).collect();
+        let mut order = Vec::with_capacity(self.len());
+        while let Some(node) = ready.pop_front() {
+            order.push(node);
+            for &next in &self.successors[node] {
+                in_degrees[next] -= 1;
+                if in_degrees[next] == 0 {
+                    ready.push_back(next);
+                }
+            }
+        }
+        if order.len() < self.len() {
+            return None;
+        }
+        return Some(order);
+    }
+
+    // Tarjan's algorithm, without recursion. Components come out in reverse topological order:
+    // no edge leads from a component to a later one.
+    pub fn strongly_connected_components(&self) -> Vec<Vec<NodeId>> {
+        let mut index = vec![usize::MAX; self.len()];
+        let mut low_link = vec![0; self.len()];
+        let mut on_stack = vec![false; self.len()];
+        let mut stack = vec![];
+        let mut components = vec![];
+        let mut next_index = 0;
+
+        for root in 0..self.len() {
+            if index[root] != usize::MAX {
+                continue;
+            }
+            // (node, position of the next successor to visit)
+            let mut call_stack = vec![(root, 0)];
+            index[root] = next_index;
+            low_link[root] = next_index;
+            next_index += 1;
+            stack.push(root);
+            on_stack[root] = true;
+
+            while let Some(&mut (node, ref mut next_successor)) = call_stack.last_mut() {
+                if let Some(&next) = self.successors[node].get(*next_successor) {
+                    *next_successor += 1;
+                    if index[next] == usize::MAX {
+                        index[next] = next_index;
+                        low_link[next] = next_index;
+                        next_index += 1;
+                        stack.push(next);
+                        on_stack[next] = true;
+                        call_stack.push((next, 0));
+                    } else if on_stack[next] {
+                        low_link[node] = low_link[node].min(index[next]);
+                    }
+                    continue;
+                }
+
+                call_stack.pop();
+                if let Some(&(parent, _)) = call_stack.last() {
+                    low_link[parent] = low_link[parent].min(low_link[node]);
+                }
+                if low_link[node] == index[node] {
+                    let mut component = vec![];
+                    while let Some(member) = stack.pop() {
+                        on_stack[member] = false;
+                        component.push(member);
+                        if member == node {
+                            break;
+                        }
+                    }
+                    components.push(component);
+                }
+            }
+        }
+        return components;
+    }
+
+    // Nodes reachable from `start`, including itself, in breadth-first order.
+    pub fn reachable_from(&self, start: NodeId) -> Vec<NodeId> {
+        let mut visited = vec![false; self.len()];
+        let mut reachable = vec![start];
+        visited[start] = true;
+        let mut i = 0;
+        while i < reachable.len() {
+            for &next in &self.successors[reachable[i]] {
+                if !visited[next] {
+                    visited[next] = true;
+                    reachable.push(next);
+                }
+            }
+            i += 1;
+        }
+        return reachable;
+    }
+
+    // Number of nodes reachable from each node, including itself. Takes a search per node.
+    pub fn reachable_counts(&self) -> Vec<usize> {
+        (0..self.len())
+            .map(|node| self.reachable_from(node).len())
+            .collect()
+    }
+
+    // Dominator tree of the nodes reachable from `root`, by Cooper, Harvey and Kennedy's
+    // iterative algorithm.
+    pub fn dominators(&self, root: NodeId) -> Dominators {
+        // Postorder numbering of the nodes reachable from root.
+        let mut postorder = vec![];
+        let mut visited = vec![false; self.len()];
+        let mut call_stack = vec![(root, 0)];
+        visited[root] = true;
+        while let Some(&mut (node, ref mut next_successor)) = call_stack.last_mut() {
+            if let Some(&next) = self.successors[node].get(*next_successor) {
+                *next_successor += 1;
+                if !visited[next] {
+                    visited[next] = true;
+                    call_stack.push((next, 0));
+                }
+            } else {
+                postorder.push(node);
+                call_stack.pop();
+            }
+        }
+        let mut postorder_number = vec![usize::MAX; self.len()];
+        for (number, &node) in postorder.iter().enumerate() {
+            postorder_number[node] = number;
+        }
+
+        let mut immediate_dominators = vec![None; self.len()];
+        immediate_dominators[root] = Some(root);
+        let intersect = |idoms: &[Option<NodeId>], mut a: NodeId, mut b: NodeId| {
+            while a != b {
+                while postorder_number[a] < postorder_number[b] {
+                    a = idoms[a].unwrap();
+                }
+                while postorder_number[b] < postorder_number[a] {
+                    b = idoms[b].unwrap();
+                }
+            }
+            a
+        };
+
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for &node in postorder.iter().rev().filter(|&&node| node != root) {
+                let mut processed = self.predecessors[node]
+                    .iter()
+                    .copied()
+                    .filter(|&p| immediate_dominators[p].is_some());
+                let Some(first) = processed.next() else {
+                    continue;
+                };
+                let new_idom =
+                    processed.fold(first, |idom, p| intersect(&immediate_dominators, p, idom));
+                if immediate_dominators[node] != Some(new_idom) {
+                    immediate_dominators[node] = Some(new_idom);
+                    changed = true;
+                }
+            }
+        }
+
+        immediate_dominators[root] = None;
+        return Dominators {
+            root,
+            immediate_dominators,
+            postorder,
+        };
+    }
+
+    // Fewest edges whose removal separates `source` from `sink`, treating each edge as one unit
+    // of capacity (Edmonds-Karp). Also returns the nodes still reachable from `source`.
+    pub fn min_cut(&self, source: NodeId, sink: NodeId) -> MinCut {
+        // Residual capacity of each (from, to) pair; parallel edges add up.
+        let mut capacity: HashMap<(NodeId, NodeId), i64> = HashMap::new();
+        for (from, to) in self.edges() {
+            *capacity.entry((from, to)).or_insert(0) += 1;
+            capacity.entry((to, from)).or_insert(0);
+        }
+        let mut residual_neighbors = vec![vec![]; self.len()];
+        for &(from, to) in capacity.keys() {
+            residual_neighbors[from].push(to);
+        }
+
+        let mut size = 0;
+        loop {
+            // Shortest augmenting path by BFS over edges with capacity left.
+            let mut parents = vec![None; self.len()];
+            let mut visited = vec![false; self.len()];
+            visited[source] = true;
+            let mut queue = VecDeque::from([source]);
+            while let Some(node) = queue.pop_front() {
+                for &next in &residual_neighbors[node] {
+                    if !visited[next] && capacity[&(node, next)] > 0 {
+                        visited[next] = true;
+                        parents[next] = Some(node);
+                        queue.push_back(next);
+                    }
+                }
+            }
+
+            if !visited[sink] {
+                let source_side = (0..self.len()).filter(|&n| visited[n]).collect();
+                return MinCut { size, source_side };
+            }
+
+            let mut node = sink;
+            while let Some(parent) = parents[node] {
+                *capacity.get_mut(&(parent, node)).unwrap() -= 1;
+                *capacity.get_mut(&(node, parent)).unwrap() += 1;
+                node = parent;
+            }
+            size += 1;
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Dominators {
+    root: NodeId,
+    // None for the root and for nodes unreachable from it.
+    immediate_dominators: Vec<Option<NodeId>>,
+    // Reachable nodes, each after everything it dominates.
+    postorder: Vec<NodeId>,
+}
+
+impl Dominators {
+    pub fn root(&self) -> NodeId {
+        self.root
+    }
+
+    // The closest node other than `node` on every path from the root to it.
+    pub fn immediate_dominator(&self, node: NodeId) -> Option<NodeId> {
+        self.immediate_dominators[node]
+    }
+
+    // Whether every path from the root to `b` passes through `a`; nodes dominate themselves.
+    pub fn dominates(&self, a: NodeId, b: NodeId) -> bool {
+        if a == b {
+            return a == self.root || self.immediate_dominators[a].is_some();
+        }
+        let mut node = b;
+        while let Some(idom) = self.immediate_dominators[node] {
+            if idom == a {
+                return true;
+            }
+            node = idom;
+        }
+        return false;
+    }
+
+    // Number of nodes each node dominates, including itself; 0 for unreachable nodes.
+    pub fn dominated_counts(&self) -> Vec<usize> {
+        let mut counts = vec![0; self.immediate_dominators.len()];
+        for &node in &self.postorder {
+            counts[node] += 1;
+            if let Some(idom) = self.immediate_dominators[node] {
+                counts[idom] += counts[node];
+            }
+        }
+        counts
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MinCut {
+    // Number of edges cut.
+    pub size: usize,
+    pub source_side: Vec<NodeId>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use googletest::prelude::*;
+
+    fn directed(edges: &[(NodeId, NodeId)]) -> Graph {
+        let mut graph = Graph::directed(0);
+        for &(from, to) in edges {
+            graph.add_edge(from, to);
+        }
+        graph
+    }
+
+    #[googletest::test]
+    fn numbers_nodes_by_symbol() {
+        expect_that!(NodeId::from(Symbol::from_index(3)), eq(3));
+    }
+
+    #[googletest::test]
+    fn stores_undirected_edges_both_ways() {
+        let mut graph = Graph::undirected(0);
+        graph.add_edge(0, 2);
+        expect_that!(graph.len(), eq(3));
+        expect_that!(graph.successors(2).to_vec(), eq(vec![0]));
+        expect_that!(graph.successors(0).to_vec(), eq(vec![2]));
+        expect_that!(graph.edges().count(), eq(2));
+    }
+
+    #[googletest::test]
+    fn sorts_topologically() {
+        let graph = directed(&[(3, 1), (1, 0), (3, 2), (2, 0)]);
+        let order = graph.topological_sort().unwrap();
+        let position = |node| order.iter().position(|&n| n == node).unwrap();
+        for (from, to) in graph.edges() {
+            expect_that!(position(from), lt(position(to)));
+        }
+        expect_that!(directed(&[(0, 1), (1, 0)]).topological_sort(), none());
+    }
+
+    #[googletest::test]
+    fn finds_strongly_connected_components() {
+        let graph = directed(&[(0, 1), (1, 2), (2, 0), (2, 3), (3, 4), (4, 3), (5, 4)]);
+        let mut components = graph.strongly_connected_components();
+        for component in components.iter_mut() {
+            component.sort();
+        }
+        expect_that!(components, eq(vec![vec![3, 4], vec![0, 1, 2], vec![5]]));
+    }
+
+    #[googletest::test]
+    fn counts_reachable_nodes() {
+        let graph = directed(&[(0, 1), (1, 2), (0, 2), (3, 0)]);
+        expect_that!(graph.reachable_counts(), eq(vec![3, 2, 1, 4]));
+    }
+
+    #[googletest::test]
+    fn computes_dominators() {
+        // 0 -> 1 -> {2, 3} -> 4 -> 5, with 6 unreachable.
+        let graph = directed(&[(0, 1), (1, 2), (1, 3), (2, 4), (3, 4), (4, 5), (6, 5)]);
+        let dominators = graph.dominators(0);
+        expect_that!(dominators.immediate_dominator(4), some(eq(1)));
+        expect_that!(dominators.immediate_dominator(5), some(eq(4)));
+        expect_that!(dominators.immediate_dominator(0), none());
+        expect_that!(dominators.dominates(1, 5), eq(true));
+        expect_that!(dominators.dominates(2, 4), eq(false));
+        expect_that!(dominators.dominates(6, 6), eq(false));
+        expect_that!(dominators.dominated_counts(), eq(vec![6, 5, 1, 1, 2, 1, 0]));
+    }
+
+    #[googletest::test]
+    fn finds_minimum_cut() {
+        // Two triangles joined by a single bridge, 2 - 3.
+        let mut graph = Graph::undirected(0);
+        for (from, to) in [(0, 1), (1, 2), (2, 0), (3, 4), (4, 5), (5, 3), (2, 3)] {
+            graph.add_edge(from, to);
+        }
+        let cut = graph.min_cut(0, 5);
+        expect_that!(cut.size, eq(1));
+        expect_that!(cut.source_side, eq(vec![0, 1, 2]));
+
+        graph.add_edge(1, 4);
+        expect_that!(graph.min_cut(0, 5).size, eq(2));
+    }
+}
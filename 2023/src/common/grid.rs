@@ -1,3 +1,12 @@
+use crate::common::debug;
+use crate::common::direction::Direction8;
+use crate::common::point::{Point, Position};
+use std::collections::{HashMap, HashSet};
+use std::fmt::{Display, Write};
+use std::fs;
+use std::io::{self, IsTerminal};
+use std::path::Path;
+
 pub type Grid<T> = Vec<Vec<T>>;
 
 pub fn parse_grid(input: &str) -> Grid<char> {
@@ -24,8 +33,741 @@ pub fn transpose_grid<T: Copy>(grid: &Grid<T>) -> Grid<T> {
         .collect()
 }
 
+#[derive(Debug, PartialEq, Eq)]
+pub enum GridParseError {
+    UnknownCharacter {
+        character: char,
+        row: usize,
+        column: usize,
+    },
+    RaggedRow {
+        row: usize,
+        expected: usize,
+        actual: usize,
+    },
+    MissingMarker(char),
+    DuplicateMarker {
+        marker: char,
+        first: Position,
+        second: Position,
+    },
+}
+
+impl Display for GridParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnknownCharacter {
+                character,
+                row,
+                column,
+            } => write!(
+                f,
+                "Unknown character {character:?} at row {row}, column {column}"
+            ),
+            Self::RaggedRow {
+                row,
+                expected,
+                actual,
+            } => write!(f, "Row {row} has length {actual}, expected {expected}"),
+            Self::MissingMarker(marker) => write!(f, "Marker {marker:?} not found"),
+            Self::DuplicateMarker {
+                marker,
+                first,
+                second,
+            } => write!(
+                f,
+                "Marker {marker:?} found at both {first:?} and {second:?}"
+            ),
+        }
+    }
+}
+
+pub struct ParsedGrid<T> {
+    pub grid: Grid<T>,
+    pub markers: HashMap<char, Position>,
+}
+
+impl<T> ParsedGrid<T> {
+    // Position of a marker registered with `GridParser::with_marker`.
+    pub fn marker(&self, marker: char) -> Position {
+        self.markers[&marker]
+    }
+}
+
+// Validating grid parser. Each character is mapped to a cell, and unknown characters or
+// ragged rows are reported as errors. Trailing whitespace (including CRLF) is ignored.
+pub struct GridParser<T> {
+    transform: Box<dyn Fn(char) -> Option<T>>,
+    markers: Vec<char>,
+}
+
+impl<T> GridParser<T> {
+    // Parser with a fallible mapping; characters mapped to None are rejected.
+    pub fn new<F>(transform: F) -> Self
+    where
+        F: Fn(char) -> Option<T> + 'static,
+    {
+        Self {
+            transform: Box::new(transform),
+            markers: vec![],
+        }
+    }
+
+    pub fn from_map(mapping: HashMap<char, T>) -> Self
+    where
+        T: Clone + 'static,
+    {
+        Self::new(move |c| mapping.get(&c).cloned())
+    }
+
+    // Locate the single occurrence of the marker character, which must also be accepted by the mapping.
+    pub fn with_marker(mut self, marker: char) -> Self {
+        self.markers.push(marker);
+        self
+    }
+
+    pub fn parse(&self, input: &str) -> Result<ParsedGrid<T>, GridParseError> {
+        let mut grid: Grid<T> = vec![];
+        let mut markers = HashMap::new();
+
+        for (i, line) in input.trim_end().lines().map(str::trim_end).enumerate() {
+            let mut row = vec![];
+            for (j, c) in line.chars().enumerate() {
+                let cell = (self.transform)(c).ok_or(GridParseError::UnknownCharacter {
+                    character: c,
+                    row: i,
+                    column: j,
+                })?;
+                if self.markers.contains(&c) {
+                    if let Some(&first) = markers.get(&c) {
+                        return Err(GridParseError::DuplicateMarker {
+                            marker: c,
+                            first,
+                            second: Point(i, j),
+                        });
+                    }
+                    markers.insert(c, Point(i, j));
+                }
+                row.push(cell);
+            }
+            if let Some(first_row) = grid.first() {
+                if first_row.len() != row.len() {
+                    return Err(GridParseError::RaggedRow {
+                        row: i,
+                        expected: first_row.len(),
+                        actual: row.len(),
+                    });
+                }
+            }
+            grid.push(row);
+        }
+
+        if let Some(&missing) = self.markers.iter().find(|m| !markers.contains_key(m)) {
+            return Err(GridParseError::MissingMarker(missing));
+        }
+        Ok(ParsedGrid { grid, markers })
+    }
+}
+
+impl GridParser<char> {
+    // Parser that keeps characters as-is, accepting only those in `allowed`.
+    pub fn allowing(allowed: &str) -> Self {
+        let allowed: HashSet<char> = allowed.chars().collect();
+        Self::new(move |c| if allowed.contains(&c) { Some(c) } else { None })
+    }
+}
+
+impl GridParser<u32> {
+    // Parser for grids of decimal digits.
+    pub fn digits() -> Self {
+        Self::new(|c| c.to_digit(10))
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Connectivity {
+    // Orthogonal neighbors only.
+    Four,
+    // Orthogonal and diagonal neighbors.
+    Eight,
+}
+
+impl Connectivity {
+    fn directions(&self) -> &'static [Direction8] {
+        match self {
+            Self::Four => &Direction8::ORTHOGONAL,
+            Self::Eight => &Direction8::ALL,
+        }
+    }
+
+    // In-bounds neighbors of the position.
+    pub fn neighbors<'a, T>(
+        &self,
+        position: Position,
+        grid: &'a Grid<T>,
+    ) -> impl Iterator<Item = Position> + 'a {
+        self.directions()
+            .iter()
+            .filter_map(move |&direction| position.step8(direction))
+            .filter(|neighbor| neighbor.is_in_bounds(grid))
+    }
+}
+
+// All positions connected to any of the starting positions through cells matching the predicate.
+// Starting positions that don't match the predicate are skipped.
+pub fn flood_fill<T, F, I>(
+    grid: &Grid<T>,
+    starting_positions: I,
+    connectivity: Connectivity,
+    predicate: F,
+) -> HashSet<Position>
+where
+    F: Fn(&T) -> bool,
+    I: IntoIterator<Item = Position>,
+{
+    let mut filled = HashSet::new();
+    let mut next_positions: Vec<_> = starting_positions
+        .into_iter()
+        .filter(|p| p.is_in_bounds(grid) && predicate(&grid[p.0][p.1]))
+        .collect();
+
+    while let Some(position) = next_positions.pop() {
+        if !filled.insert(position) {
+            continue;
+        }
+        for neighbor in connectivity.neighbors(position, grid) {
+            if !filled.contains(&neighbor) && predicate(&grid[neighbor.0][neighbor.1]) {
+                next_positions.push(neighbor);
+            }
+        }
+    }
+    filled
+}
+
+// Flood fill from every border cell matching the predicate. Cells matching the predicate that
+// are not returned are enclosed, e.g. the inside of a loop.
+pub fn flood_fill_from_border<T, F>(
+    grid: &Grid<T>,
+    connectivity: Connectivity,
+    predicate: F,
+) -> HashSet<Position>
+where
+    F: Fn(&T) -> bool,
+{
+    let num_rows = grid.len();
+    let num_columns = grid.first().map_or(0, |row| row.len());
+    let border = (0..num_rows)
+        .flat_map(|i| [Point(i, 0), Point(i, num_columns.saturating_sub(1))])
+        .chain((0..num_columns).flat_map(|j| [Point(0, j), Point(num_rows - 1, j)]));
+    flood_fill(grid, border, connectivity, predicate)
+}
+
+#[derive(Debug, Clone)]
+pub struct Region {
+    pub positions: Vec<Position>,
+    // Number of cell edges between the region and anything outside it, including the grid border.
+    pub perimeter: usize,
+}
+
+impl Region {
+    pub fn area(&self) -> usize {
+        self.positions.len()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RegionLabels {
+    // Index into `regions` for each cell, or None for cells not in any region.
+    pub labels: Grid<Option<usize>>,
+    pub regions: Vec<Region>,
+}
+
+// Label connected components of cells matching the predicate.
+pub fn label_regions<T, F>(grid: &Grid<T>, connectivity: Connectivity, predicate: F) -> RegionLabels
+where
+    F: Fn(&T) -> bool,
+{
+    label_regions_by(grid, connectivity, |_, _| true, predicate)
+}
+
+// Label connected components where neighboring cells belong together if `same_region` holds,
+// e.g. `|a, b| a == b` to split a grid into regions of equal cells. Only cells matching
+// `predicate` are labeled.
+pub fn label_regions_by<T, F, P>(
+    grid: &Grid<T>,
+    connectivity: Connectivity,
+    same_region: F,
+    predicate: P,
+) -> RegionLabels
+where
+    F: Fn(&T, &T) -> bool,
+    P: Fn(&T) -> bool,
+{
+    let mut labels: Grid<Option<usize>> = grid.iter().map(|row| vec![None; row.len()]).collect();
+    let mut regions = vec![];
+
+    for (i, row) in grid.iter().enumerate() {
+        for (j, cell) in row.iter().enumerate() {
+            if labels[i][j].is_some() || !predicate(cell) {
+                continue;
+            }
+
+            let label = regions.len();
+            let mut positions = vec![];
+            let mut next_positions = vec![Point(i, j)];
+            labels[i][j] = Some(label);
+            while let Some(position) = next_positions.pop() {
+                positions.push(position);
+                let current = &grid[position.0][position.1];
+                for neighbor in connectivity.neighbors(position, grid) {
+                    let next = &grid[neighbor.0][neighbor.1];
+                    if labels[neighbor.0][neighbor.1].is_none()
+                        && predicate(next)
+                        && same_region(current, next)
+                    {
+                        labels[neighbor.0][neighbor.1] = Some(label);
+                        next_positions.push(neighbor);
+                    }
+                }
+            }
+
+            let perimeter = positions
+                .iter()
+                .map(|&position| {
+                    4 - Connectivity::Four
+                        .neighbors(position, grid)
+                        .filter(|n| labels[n.0][n.1] == Some(label))
+                        .count()
+                })
+                .sum();
+            regions.push(Region {
+                positions,
+                perimeter,
+            });
+        }
+    }
+
+    RegionLabels { labels, regions }
+}
+
 pub fn print_grid(grid: &Grid<char>) {
     for row in grid {
         println!("{}", String::from_iter(row.iter()));
     }
 }
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub enum Color {
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+    Black,
+    Rgb(u8, u8, u8),
+}
+
+impl Color {
+    fn ansi_code(&self) -> String {
+        match self {
+            Self::Red => "31".to_string(),
+            Self::Green => "32".to_string(),
+            Self::Yellow => "33".to_string(),
+            Self::Blue => "34".to_string(),
+            Self::Magenta => "35".to_string(),
+            Self::Cyan => "36".to_string(),
+            Self::White => "37".to_string(),
+            Self::Black => "30".to_string(),
+            Self::Rgb(r, g, b) => format!("38;2;{r};{g};{b}"),
+        }
+    }
+
+    pub fn rgb(&self) -> (u8, u8, u8) {
+        match self {
+            Self::Red => (205, 49, 49),
+            Self::Green => (13, 188, 121),
+            Self::Yellow => (229, 229, 16),
+            Self::Blue => (36, 114, 200),
+            Self::Magenta => (188, 63, 188),
+            Self::Cyan => (17, 168, 205),
+            Self::White => (229, 229, 229),
+            Self::Black => (0, 0, 0),
+            Self::Rgb(r, g, b) => (*r, *g, *b),
+        }
+    }
+
+    fn hex(&self) -> String {
+        let (r, g, b) = self.rgb();
+        format!("#{r:02x}{g:02x}{b:02x}")
+    }
+}
+
+// Renders grids to the terminal, coloring highlighted positions with ANSI escapes.
+// Colors are only emitted when stdout is a terminal, unless overridden with `with_color`.
+pub struct GridRenderer {
+    highlights: Vec<(HashSet<Position>, Color)>,
+    use_color: bool,
+}
+
+impl Default for GridRenderer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GridRenderer {
+    pub fn new() -> Self {
+        Self {
+            highlights: vec![],
+            use_color: std::io::stdout().is_terminal(),
+        }
+    }
+
+    pub fn with_color(mut self, use_color: bool) -> Self {
+        self.use_color = use_color;
+        self
+    }
+
+    // Highlight the given positions. Highlights added later take precedence over earlier ones.
+    pub fn highlight<I>(mut self, positions: I, color: Color) -> Self
+    where
+        I: IntoIterator<Item = Position>,
+    {
+        self.highlights
+            .push((positions.into_iter().collect(), color));
+        self
+    }
+
+    fn color_at(&self, position: &Position) -> Option<Color> {
+        self.highlights
+            .iter()
+            .rev()
+            .find(|(positions, _)| positions.contains(position))
+            .map(|(_, color)| *color)
+    }
+
+    pub fn render<T: Display>(&self, grid: &Grid<T>) -> String {
+        let mut output = String::new();
+        for (i, row) in grid.iter().enumerate() {
+            for (j, cell) in row.iter().enumerate() {
+                match self.color_at(&Point(i, j)) {
+                    Some(color) if self.use_color => {
+                        write!(output, "\x1b[1;{}m{cell}\x1b[0m", color.ansi_code()).unwrap()
+                    }
+                    _ => write!(output, "{cell}").unwrap(),
+                }
+            }
+            output.push('\n');
+        }
+        output
+    }
+
+    pub fn print<T: Display>(&self, grid: &Grid<T>) {
+        print!("{}", self.render(grid));
+    }
+}
+
+// Exports grids as images, mapping each cell to a color and optionally overlaying paths.
+pub struct ImageExporter<T> {
+    color_of: Box<dyn Fn(&T) -> Color>,
+    cell_size: usize,
+    overlays: Vec<(Vec<Position>, Color)>,
+}
+
+impl<T> ImageExporter<T> {
+    pub fn new<F>(color_of: F) -> Self
+    where
+        F: Fn(&T) -> Color + 'static,
+    {
+        Self {
+            color_of: Box::new(color_of),
+            cell_size: 4,
+            overlays: vec![],
+        }
+    }
+
+    // Size in pixels of each cell's side.
+    pub fn with_cell_size(mut self, cell_size: usize) -> Self {
+        self.cell_size = cell_size.max(1);
+        self
+    }
+
+    // Draw a path on top of the grid. Overlays added later are drawn on top of earlier ones.
+    pub fn overlay_path<I>(mut self, path: I, color: Color) -> Self
+    where
+        I: IntoIterator<Item = Position>,
+    {
+        self.overlays.push((path.into_iter().collect(), color));
+        self
+    }
+
+    // Ragged grids are as wide as their longest row, with short rows padded in black.
+    pub(crate) fn dimensions(&self, grid: &Grid<T>) -> (usize, usize) {
+        let num_columns = grid.iter().map(|row| row.len()).max().unwrap_or(0);
+        (num_columns * self.cell_size, grid.len() * self.cell_size)
+    }
+
+    // Row-major RGB pixels, with path overlays painted as a square inset within each cell.
+    pub(crate) fn to_pixels(&self, grid: &Grid<T>) -> Vec<u8> {
+        let (width, height) = self.dimensions(grid);
+        let mut pixels = vec![0_u8; width * height * 3];
+        let mut paint = |position: Position, inset: usize, color: Color| {
+            let (r, g, b) = color.rgb();
+            for y in inset..self.cell_size - inset {
+                for x in inset..self.cell_size - inset {
+                    let offset = ((position.0 * self.cell_size + y) * width
+                        + position.1 * self.cell_size
+                        + x)
+                        * 3;
+                    pixels[offset..offset + 3].copy_from_slice(&[r, g, b]);
+                }
+            }
+        };
+
+        for (i, row) in grid.iter().enumerate() {
+            for (j, cell) in row.iter().enumerate() {
+                paint(Point(i, j), 0, (self.color_of)(cell));
+            }
+        }
+        let inset = self.cell_size / 4;
+        for (path, color) in &self.overlays {
+            for position in path {
+                if position.is_in_bounds(grid) {
+                    paint(*position, inset, *color);
+                }
+            }
+        }
+        pixels
+    }
+
+    pub fn to_ppm(&self, grid: &Grid<T>) -> Vec<u8> {
+        let (width, height) = self.dimensions(grid);
+        let mut output = format!("P6\n{width} {height}\n255\n").into_bytes();
+        output.extend(self.to_pixels(grid));
+        output
+    }
+
+    pub fn to_png(&self, grid: &Grid<T>) -> Vec<u8> {
+        let (width, height) = self.dimensions(grid);
+        let mut output = vec![];
+        let mut encoder = png::Encoder::new(&mut output, width as u32, height as u32);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header().expect("Failed to write PNG header");
+        writer
+            .write_image_data(&self.to_pixels(grid))
+            .expect("Failed to write PNG data");
+        writer.finish().expect("Failed to finish PNG");
+        output
+    }
+
+    // Cells are drawn as rects, and paths as polylines through the cell centers.
+    pub fn to_svg(&self, grid: &Grid<T>) -> String {
+        let (width, height) = self.dimensions(grid);
+        let size = self.cell_size;
+        let mut output = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" viewBox=\"0 0 {width} {height}\">\n"
+        );
+        for (i, row) in grid.iter().enumerate() {
+            for (j, cell) in row.iter().enumerate() {
+                writeln!(
+                    output,
+                    "<rect x=\"{}\" y=\"{}\" width=\"{size}\" height=\"{size}\" fill=\"{}\"/>",
+                    j * size,
+                    i * size,
+                    (self.color_of)(cell).hex()
+                )
+                .unwrap();
+            }
+        }
+        for (path, color) in &self.overlays {
+            let points = path
+                .iter()
+                .map(|p| format!("{},{}", p.1 * size + size / 2, p.0 * size + size / 2))
+                .collect::<Vec<_>>()
+                .join(" ");
+            writeln!(
+                output,
+                "<polyline points=\"{points}\" fill=\"none\" stroke=\"{}\" stroke-width=\"{}\"/>",
+                color.hex(),
+                (size / 3).max(1)
+            )
+            .unwrap();
+        }
+        output.push_str("</svg>\n");
+        output
+    }
+
+    // Write the grid to a file, picking the format from the extension: ppm, png or svg.
+    pub fn write<P: AsRef<Path>>(&self, grid: &Grid<T>, path: P) -> io::Result<()> {
+        let path = path.as_ref();
+        let contents = match path.extension().and_then(|e| e.to_str()) {
+            Some("ppm") => self.to_ppm(grid),
+            Some("png") => self.to_png(grid),
+            Some("svg") => self.to_svg(grid).into_bytes(),
+            _ => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!("Unsupported image format for {}", path.display()),
+                ))
+            }
+        };
+        fs::write(path, contents)
+    }
+
+    // Write the grid into the debug output directory, if one was requested on the command line.
+    pub fn write_debug(&self, grid: &Grid<T>, file_name: &str) {
+        if let Some(path) = debug::output_path(file_name) {
+            if let Err(e) = self.write(grid, &path) {
+                println!("Failed to write {}: {e}", path.display());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use googletest::prelude::*;
+
+    #[googletest::test]
+    fn renders_grid_without_color() {
+        let grid = parse_transform_grid("12\n34", |c| c.to_digit(10).unwrap());
+        let renderer = GridRenderer::new()
+            .with_color(false)
+            .highlight([Point(0, 0)], Color::Red);
+
+        expect_that!(renderer.render(&grid), eq("12\n34\n"));
+    }
+
+    #[googletest::test]
+    fn renders_highlights_with_later_colors_taking_precedence() {
+        let grid = parse_grid("ab\ncd");
+        let renderer = GridRenderer::new()
+            .with_color(true)
+            .highlight([Point(0, 0), Point(1, 1)], Color::Red)
+            .highlight([Point(1, 1)], Color::Green);
+
+        expect_that!(
+            renderer.render(&grid),
+            eq("\x1b[1;31ma\x1b[0mb\nc\x1b[1;32md\x1b[0m\n")
+        );
+    }
+
+    #[googletest::test]
+    fn exports_ppm_with_overlay() {
+        let grid = parse_grid("#.");
+        let exporter = ImageExporter::new(|c: &char| match c {
+            '#' => Color::White,
+            _ => Color::Black,
+        })
+        .with_cell_size(1)
+        .overlay_path([Point(0, 1)], Color::Rgb(1, 2, 3));
+
+        let mut expected = b"P6\n2 1\n255\n".to_vec();
+        expected.extend([229, 229, 229, 1, 2, 3]);
+        expect_that!(exporter.to_ppm(&grid), eq(expected));
+    }
+
+    #[googletest::test]
+    fn pads_ragged_rows() {
+        let grid = vec![vec!['#'], vec!['#', '#']];
+        let exporter = ImageExporter::new(|_: &char| Color::White).with_cell_size(1);
+
+        let mut expected = b"P6\n2 2\n255\n".to_vec();
+        expected.extend([229, 229, 229, 0, 0, 0, 229, 229, 229, 229, 229, 229]);
+        expect_that!(exporter.to_ppm(&grid), eq(expected));
+    }
+
+    #[googletest::test]
+    fn exports_svg_with_path() {
+        let grid = parse_grid("..");
+        let svg = ImageExporter::new(|_: &char| Color::Black)
+            .with_cell_size(2)
+            .overlay_path([Point(0, 0), Point(0, 1)], Color::Red)
+            .to_svg(&grid);
+
+        expect_that!(
+            svg,
+            contains_substring("<rect x=\"2\" y=\"0\" width=\"2\" height=\"2\" fill=\"#000000\"/>")
+        );
+        expect_that!(svg, contains_substring("<polyline points=\"1,1 3,1\""));
+    }
+
+    #[googletest::test]
+    fn parses_grid_with_marker_and_crlf() {
+        let parsed = GridParser::allowing(".#S")
+            .with_marker('S')
+            .parse(".#.  \r\n.S.\r\n\r\n")
+            .unwrap();
+
+        expect_that!(parsed.grid, eq(parse_grid(".#.\n.S.")));
+        expect_that!(parsed.marker('S'), eq(Point(1, 1)));
+    }
+
+    #[googletest::test]
+    fn reports_grid_parse_errors() {
+        let parser = GridParser::digits();
+        expect_that!(
+            parser.parse("12\n3x").err(),
+            some(eq(GridParseError::UnknownCharacter {
+                character: 'x',
+                row: 1,
+                column: 1
+            }))
+        );
+        expect_that!(
+            parser.parse("12\n3").err(),
+            some(eq(GridParseError::RaggedRow {
+                row: 1,
+                expected: 2,
+                actual: 1
+            }))
+        );
+
+        let parser = GridParser::allowing(".S").with_marker('S');
+        expect_that!(
+            parser.parse("..").err(),
+            some(eq(GridParseError::MissingMarker('S')))
+        );
+        expect_that!(
+            parser.parse("S.\n.S").err(),
+            some(eq(GridParseError::DuplicateMarker {
+                marker: 'S',
+                first: Point(0, 0),
+                second: Point(1, 1)
+            }))
+        );
+    }
+
+    #[googletest::test]
+    fn flood_fills_from_border_to_find_enclosed_cells() {
+        let grid = parse_grid("#####\n#...#\n#.#.#\n#####\n.....");
+        let outside = flood_fill_from_border(&grid, Connectivity::Four, |&c| c == '.');
+
+        expect_that!(outside.len(), eq(5));
+        expect_that!(outside.contains(&Point(1, 1)), eq(false));
+
+        let inside = flood_fill(&grid, [Point(1, 1)], Connectivity::Four, |&c| c == '.');
+        expect_that!(inside.len(), eq(5));
+    }
+
+    #[googletest::test]
+    fn labels_regions_with_area_and_perimeter() {
+        let grid = parse_grid("AAB\nABB\nCCB");
+
+        let four = label_regions_by(&grid, Connectivity::Four, |a, b| a == b, |_| true);
+        expect_that!(four.regions.len(), eq(3));
+        let region_a = &four.regions[four.labels[0][0].unwrap()];
+        expect_that!(region_a.area(), eq(3));
+        expect_that!(region_a.perimeter, eq(8));
+        expect_that!(four.labels[1][1], eq(four.labels[2][2]));
+
+        let eight = label_regions(&grid, Connectivity::Eight, |&c| c != 'B');
+        expect_that!(eight.regions.len(), eq(1));
+        expect_that!(eight.regions[0].area(), eq(5));
+        expect_that!(eight.labels[0][2], none());
+    }
+}
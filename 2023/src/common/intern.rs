@@ -0,0 +1,82 @@
+use std::collections::HashMap;
+
+// Dense id for an interned name, usable as an index into vectors of `Interner::len()` entries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct Symbol(u32);
+
+impl Symbol {
+    pub fn from_index(index: usize) -> Self {
+        Self(u32::try_from(index).expect("Too many symbols"))
+    }
+
+    pub fn index(self) -> usize {
+        self.0 as usize
+    }
+}
+
+// Assigns symbols to names in order of first appearance, so lookups after parsing are plain
+// vector indexing rather than string hashing.
+#[derive(Debug, Clone, Default)]
+pub struct Interner {
+    symbols: HashMap<String, Symbol>,
+    names: Vec<String>,
+}
+
+impl Interner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Symbol for `name`, adding it if it's new.
+    pub fn intern(&mut self, name: &str) -> Symbol {
+        if let Some(&symbol) = self.symbols.get(name) {
+            return symbol;
+        }
+        let symbol = Symbol::from_index(self.names.len());
+        self.symbols.insert(name.to_string(), symbol);
+        self.names.push(name.to_string());
+        return symbol;
+    }
+
+    pub fn get(&self, name: &str) -> Option<Symbol> {
+        self.symbols.get(name).copied()
+    }
+
+    pub fn name(&self, symbol: Symbol) -> &str {
+        &self.names[symbol.index()]
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Symbol, &str)> {
+        self.names
+            .iter()
+            .enumerate()
+            .map(|(index, name)| (Symbol::from_index(index), name.as_str()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use googletest::prelude::*;
+
+    #[googletest::test]
+    fn interns_names_densely() {
+        let mut names = Interner::new();
+        let broadcaster = names.intern("broadcaster");
+        let rx = names.intern("rx");
+        expect_that!(broadcaster.index(), eq(0));
+        expect_that!(rx.index(), eq(1));
+        expect_that!(names.intern("broadcaster"), eq(broadcaster));
+        expect_that!(names.get("zh"), none());
+        expect_that!(names.name(rx), eq("rx"));
+        expect_that!(names.len(), eq(2));
+    }
+}
@@ -0,0 +1,314 @@
+use num::Integer;
+
+// A half-open interval [start, end); empty whenever start >= end.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash, PartialOrd, Ord)]
+pub struct Interval<T> {
+    pub start: T,
+    pub end: T,
+}
+
+impl<T> Interval<T>
+where
+    T: Integer + Copy,
+{
+    pub fn new(start: T, end: T) -> Self {
+        Self { start, end }
+    }
+
+    pub fn with_len(start: T, len: T) -> Self {
+        Self {
+            start,
+            end: start + len,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start >= self.end
+    }
+
+    pub fn len(&self) -> T {
+        if self.is_empty() {
+            T::zero()
+        } else {
+            self.end - self.start
+        }
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        self.start <= value && value < self.end
+    }
+
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        let intersection = Self::new(self.start.max(other.start), self.end.min(other.end));
+        if intersection.is_empty() {
+            None
+        } else {
+            Some(intersection)
+        }
+    }
+
+    // The parts below `value` and at or above it, either of which may be empty.
+    pub fn split_at(&self, value: T) -> (Self, Self) {
+        let value = value.clamp(self.start, self.end.max(self.start));
+        (Self::new(self.start, value), Self::new(value, self.end))
+    }
+}
+
+// A set of values stored as sorted, disjoint, non-adjacent intervals.
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct IntervalSet<T> {
+    intervals: Vec<Interval<T>>,
+}
+
+impl<T> IntervalSet<T>
+where
+    T: Integer + Copy,
+{
+    pub fn new() -> Self {
+        Self { intervals: vec![] }
+    }
+
+    pub fn insert(&mut self, interval: Interval<T>) {
+        if interval.is_empty() {
+            return;
+        }
+        // Intervals entirely before or after the new one survive; anything touching it merges.
+        let before = self.intervals.partition_point(|i| i.end < interval.start);
+        let after = self.intervals.partition_point(|i| i.start <= interval.end);
+        let mut merged = interval;
+        if before < after {
+            merged.start = merged.start.min(self.intervals[before].start);
+            merged.end = merged.end.max(self.intervals[after - 1].end);
+        }
+        self.intervals.splice(before..after, [merged]);
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        let index = self.intervals.partition_point(|i| i.end <= value);
+        self.intervals
+            .get(index)
+            .is_some_and(|interval| interval.contains(value))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    // Number of values in the set.
+    pub fn len(&self) -> T {
+        self.intervals
+            .iter()
+            .fold(T::zero(), |total, interval| total + interval.len())
+    }
+
+    pub fn min(&self) -> Option<T> {
+        self.intervals.first().map(|interval| interval.start)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Interval<T>> {
+        self.intervals.iter()
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        let mut union = self.clone();
+        for &interval in &other.intervals {
+            union.insert(interval);
+        }
+        union
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut intersection = Self::new();
+        let (mut i, mut j) = (0, 0);
+        while i < self.intervals.len() && j < other.intervals.len() {
+            let (a, b) = (self.intervals[i], other.intervals[j]);
+            if let Some(overlap) = a.intersection(&b) {
+                intersection.intervals.push(overlap);
+            }
+            if a.end < b.end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        intersection
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut difference = Self::new();
+        let mut j = 0;
+        for &interval in &self.intervals {
+            let mut remaining = interval;
+            while j < other.intervals.len() && other.intervals[j].end <= remaining.start {
+                j += 1;
+            }
+            let mut k = j;
+            while k < other.intervals.len() && other.intervals[k].start < remaining.end {
+                let (below, _) = remaining.split_at(other.intervals[k].start);
+                let (_, above) = remaining.split_at(other.intervals[k].end);
+                if !below.is_empty() {
+                    difference.intervals.push(below);
+                }
+                remaining = above;
+                k += 1;
+            }
+            if !remaining.is_empty() {
+                difference.intervals.push(remaining);
+            }
+        }
+        difference
+    }
+}
+
+impl<T> FromIterator<Interval<T>> for IntervalSet<T>
+where
+    T: Integer + Copy,
+{
+    fn from_iter<I: IntoIterator<Item = Interval<T>>>(iter: I) -> Self {
+        let mut set = Self::new();
+        for interval in iter {
+            set.insert(interval);
+        }
+        set
+    }
+}
+
+// A piecewise map that shifts each source interval onto a destination; values outside every
+// source interval map to themselves.
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub struct RangeMap<T> {
+    // Sorted, non-overlapping source intervals and where each one starts mapping to.
+    pieces: Vec<(Interval<T>, T)>,
+}
+
+impl<T> RangeMap<T>
+where
+    T: Integer + Copy,
+{
+    pub fn new() -> Self {
+        Self { pieces: vec![] }
+    }
+
+    // Panics if `source` overlaps an interval that's already mapped.
+    pub fn insert(&mut self, source: Interval<T>, destination_start: T) {
+        if source.is_empty() {
+            return;
+        }
+        let index = self.pieces.partition_point(|(i, _)| i.end <= source.start);
+        if self
+            .pieces
+            .get(index)
+            .is_some_and(|(i, _)| i.start < source.end)
+        {
+            panic!("Overlapping source intervals in range map");
+        }
+        self.pieces.insert(index, (source, destination_start));
+    }
+
+    pub fn get(&self, value: T) -> T {
+        let index = self.pieces.partition_point(|(i, _)| i.end <= value);
+        match self.pieces.get(index) {
+            Some((source, destination_start)) if source.contains(value) => {
+                *destination_start + (value - source.start)
+            }
+            _ => value,
+        }
+    }
+
+    // Images of every value in `interval`, splitting it wherever it crosses a piece boundary.
+    pub fn map_interval(&self, interval: Interval<T>) -> IntervalSet<T> {
+        let mut mapped = IntervalSet::new();
+        let mut remaining = interval;
+        let first = self
+            .pieces
+            .partition_point(|(i, _)| i.end <= remaining.start);
+        for (source, destination_start) in &self.pieces[first..] {
+            if remaining.is_empty() || remaining.end <= source.start {
+                break;
+            }
+            let (unmapped, rest) = remaining.split_at(source.start);
+            let (inside, rest) = rest.split_at(source.end);
+            mapped.insert(unmapped);
+            if !inside.is_empty() {
+                let start = *destination_start + (inside.start - source.start);
+                mapped.insert(Interval::with_len(start, inside.len()));
+            }
+            remaining = rest;
+        }
+        mapped.insert(remaining);
+        mapped
+    }
+
+    pub fn map_set(&self, set: &IntervalSet<T>) -> IntervalSet<T> {
+        let mut mapped = IntervalSet::new();
+        for &interval in set.iter() {
+            mapped = mapped.union(&self.map_interval(interval));
+        }
+        mapped
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use googletest::prelude::*;
+
+    fn set(intervals: &[(i64, i64)]) -> IntervalSet<i64> {
+        intervals
+            .iter()
+            .map(|&(start, end)| Interval::new(start, end))
+            .collect()
+    }
+
+    #[googletest::test]
+    fn splits_intervals() {
+        let interval = Interval::new(1, 4001);
+        expect_that!(
+            interval.split_at(100),
+            eq((Interval::new(1, 100), Interval::new(100, 4001)))
+        );
+        expect_that!(interval.split_at(0).0.len(), eq(0));
+        expect_that!(interval.split_at(5000).1.len(), eq(0));
+        expect_that!(interval.intersection(&Interval::new(4001, 5000)), none());
+    }
+
+    #[googletest::test]
+    fn merges_touching_and_overlapping_intervals() {
+        let intervals = set(&[(5, 7), (0, 2), (2, 3), (10, 12), (6, 11)]);
+        expect_that!(intervals, eq(set(&[(0, 3), (5, 12)])));
+        expect_that!(intervals.len(), eq(10));
+        expect_that!(intervals.contains(3), eq(false));
+        expect_that!(intervals.contains(11), eq(true));
+        expect_that!(intervals.min(), some(eq(0)));
+    }
+
+    #[googletest::test]
+    fn combines_interval_sets() {
+        let a = set(&[(0, 10), (20, 30)]);
+        let b = set(&[(5, 25), (28, 40)]);
+        expect_that!(a.union(&b), eq(set(&[(0, 40)])));
+        expect_that!(a.intersection(&b), eq(set(&[(5, 10), (20, 25), (28, 30)])));
+        expect_that!(a.difference(&b), eq(set(&[(0, 5), (25, 28)])));
+        expect_that!(b.difference(&a), eq(set(&[(10, 20), (30, 40)])));
+    }
+
+    #[googletest::test]
+    fn pushes_intervals_through_range_map() {
+        // The seed-to-soil map from the day 5 example.
+        let mut map = RangeMap::new();
+        map.insert(Interval::with_len(98, 2), 50);
+        map.insert(Interval::with_len(50, 48), 52);
+
+        expect_that!(map.get(79), eq(81));
+        expect_that!(map.get(98), eq(50));
+        expect_that!(map.get(10), eq(10));
+        expect_that!(
+            map.map_interval(Interval::new(60, 99)),
+            eq(set(&[(50, 51), (62, 100)]))
+        );
+        expect_that!(
+            map.map_set(&set(&[(79, 93), (55, 68)])),
+            eq(set(&[(57, 70), (81, 95)]))
+        );
+    }
+}
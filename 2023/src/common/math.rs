@@ -0,0 +1,254 @@
+use crate::common::interval::Interval;
+use num::Integer;
+
+pub mod polynomial;
+
+// Largest r with r * r <= n.
+pub fn isqrt(n: u128) -> u128 {
+    if n < 2 {
+        return n;
+    }
+    // Newton's method from a power of two above the root decreases monotonically onto it.
+    let mut root = 1_u128 << ((128 - n.leading_zeros()) / 2 + 1);
+    loop {
+        let next = (root + n / root) / 2;
+        if next >= root {
+            return root;
+        }
+        root = next;
+    }
+}
+
+// Division rounding towards negative infinity, unlike `/` which truncates towards zero.
+pub fn floor_div<T: Integer>(a: T, b: T) -> T {
+    a.div_floor(&b)
+}
+
+// Division rounding towards positive infinity.
+pub fn ceil_div<T: Integer>(a: T, b: T) -> T {
+    a.div_ceil(&b)
+}
+
+// Integers x with a*x^2 + b*x + c > 0, as a (possibly empty) interval. Returns None when there
+// are infinitely many, i.e. unless the parabola opens downwards or the polynomial is a constant.
+pub fn quadratic_positive_interval(a: i128, b: i128, c: i128) -> Option<Interval<i128>> {
+    let is_positive = |x: i128| (a * x + b) * x + c > 0;
+    let empty = Interval::new(0, 0);
+
+    if a > 0 || (a == 0 && b != 0) || (a == 0 && c > 0) {
+        return None;
+    } else if a == 0 {
+        return Some(empty);
+    }
+
+    // Solutions lie strictly between the roots (-b -+ sqrt(d)) / 2a.
+    let discriminant = b * b - 4 * a * c;
+    if discriminant <= 0 {
+        return Some(empty);
+    }
+    let root = isqrt(discriminant as u128) as i128;
+    // The rounded-down square root is within one of the real one, so widen both bounds by that
+    // much and then step inwards to the first values that actually satisfy the inequality.
+    let mut low = floor_div(b - root - 1, -2 * a);
+    let mut high = ceil_div(b + root + 1, -2 * a);
+    while low <= high && !is_positive(low) {
+        low += 1;
+    }
+    while high >= low && !is_positive(high) {
+        high -= 1;
+    }
+    if low > high {
+        return Some(empty);
+    }
+    return Some(Interval::new(low, high + 1));
+}
+
+// Number of integers x with a*x^2 + b*x + c > 0, or None when there are infinitely many.
+pub fn count_quadratic_positive(a: i128, b: i128, c: i128) -> Option<u128> {
+    quadratic_positive_interval(a, b, c).map(|interval| interval.len() as u128)
+}
+
+// Returns (g, x, y) with a*x + b*y = g, where g = gcd(a, b) is non-negative.
+pub fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (1, 0);
+    let (mut old_y, mut y) = (0, 1);
+    while r != 0 {
+        let quotient = old_r / r;
+        (old_r, r) = (r, old_r - quotient * r);
+        (old_x, x) = (x, old_x - quotient * x);
+        (old_y, y) = (y, old_y - quotient * y);
+    }
+    if old_r < 0 {
+        return (-old_r, -old_x, -old_y);
+    }
+    return (old_r, old_x, old_y);
+}
+
+// The x in [0, modulus) with a*x = 1 (mod modulus), if a and modulus are coprime.
+pub fn mod_inverse(a: i128, modulus: i128) -> Option<i128> {
+    let (g, x, _) = extended_gcd(a.rem_euclid(modulus), modulus);
+    if g != 1 {
+        return None;
+    }
+    return Some(x.rem_euclid(modulus));
+}
+
+// base^exponent mod modulus, by repeated squaring. Needs modulus < 2^63 to avoid overflow.
+pub fn mod_pow(base: i128, mut exponent: u128, modulus: i128) -> i128 {
+    let mut result = 1 % modulus;
+    let mut base = base.rem_euclid(modulus);
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = result * base % modulus;
+        }
+        base = base * base % modulus;
+        exponent >>= 1;
+    }
+    return result;
+}
+
+// All integers x with x = residue (mod modulus).
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
+pub struct Congruence {
+    pub residue: i128,
+    pub modulus: i128,
+}
+
+impl Congruence {
+    pub fn new(residue: i128, modulus: i128) -> Self {
+        Self {
+            residue: residue.rem_euclid(modulus),
+            modulus,
+        }
+    }
+
+    // Integers satisfying both congruences; the moduli needn't be coprime. None if there are none.
+    pub fn combine(&self, other: &Self) -> Option<Self> {
+        let (g, p, _) = extended_gcd(self.modulus, other.modulus);
+        let difference = other.residue - self.residue;
+        if difference % g != 0 {
+            return None;
+        }
+        // self.modulus * p = g (mod other.modulus), so stepping self.residue by multiples of
+        // self.modulus reaches other.residue after (difference / g) * p of them.
+        let step_modulus = other.modulus / g;
+        let steps = (difference / g % step_modulus * p).rem_euclid(step_modulus);
+        let modulus = self.modulus * step_modulus;
+        return Some(Self::new(self.residue + self.modulus * steps, modulus));
+    }
+
+    // Smallest solution that is at least `min`.
+    pub fn first_at_least(&self, min: i128) -> i128 {
+        return self.residue + ceil_div(min - self.residue, self.modulus) * self.modulus;
+    }
+}
+
+// Chinese Remainder Theorem: integers satisfying every congruence, or None if there are none.
+pub fn crt<I: IntoIterator<Item = Congruence>>(congruences: I) -> Option<Congruence> {
+    congruences
+        .into_iter()
+        .try_fold(Congruence::new(0, 1), |combined, congruence| {
+            combined.combine(&congruence)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use googletest::prelude::*;
+
+    #[googletest::test]
+    fn computes_integer_square_roots() {
+        expect_that!(isqrt(0), eq(0));
+        expect_that!(isqrt(15), eq(3));
+        expect_that!(isqrt(16), eq(4));
+        expect_that!(isqrt(10_u128.pow(30) - 1), eq(10_u128.pow(15) - 1));
+        expect_that!(isqrt(10_u128.pow(30)), eq(10_u128.pow(15)));
+        expect_that!(isqrt(u128::MAX), eq(u64::MAX as u128));
+    }
+
+    #[googletest::test]
+    fn rounds_division_towards_infinities() {
+        expect_that!(floor_div(-7, 2), eq(-4));
+        expect_that!(ceil_div(-7, 2), eq(-3));
+        expect_that!(floor_div(7, 2), eq(3));
+        expect_that!(ceil_div(7, 2), eq(4));
+        expect_that!(ceil_div(6, 2), eq(3));
+    }
+
+    #[googletest::test]
+    fn solves_quadratic_inequalities() {
+        // x * (7 - x) > 9
+        expect_that!(
+            quadratic_positive_interval(-1, 7, -9),
+            some(eq(Interval::new(2, 6)))
+        );
+        // x * (30 - x) > 200 touches the bound exactly at both roots, 10 and 20.
+        expect_that!(count_quadratic_positive(-1, 30, -200), some(eq(9)));
+        expect_that!(count_quadratic_positive(-1, 2, -1), some(eq(0)));
+        expect_that!(count_quadratic_positive(0, 0, -1), some(eq(0)));
+        expect_that!(count_quadratic_positive(0, 1, 0), none());
+        expect_that!(count_quadratic_positive(1, 0, -1), none());
+    }
+
+    #[googletest::test]
+    fn solves_quadratic_inequalities_with_large_values() {
+        // x * (2e9 - x) peaks at exactly 1e18, where f32 can't tell the neighbours apart.
+        let time = 2_000_000_000;
+        let peak = 10_i128.pow(18);
+        expect_that!(count_quadratic_positive(-1, time, -(peak - 1)), some(eq(1)));
+        expect_that!(count_quadratic_positive(-1, time, -peak), some(eq(0)));
+        expect_that!(
+            quadratic_positive_interval(-1, time, -(peak - 1)),
+            some(eq(Interval::new(1_000_000_000, 1_000_000_001)))
+        );
+        expect_that!(
+            count_quadratic_positive(-3, 10_i128.pow(15), 0),
+            some(eq(333_333_333_333_333))
+        );
+    }
+
+    #[googletest::test]
+    fn computes_extended_gcd_and_inverses() {
+        let (g, x, y) = extended_gcd(240, 46);
+        expect_that!(g, eq(2));
+        expect_that!(240 * x + 46 * y, eq(2));
+        expect_that!(extended_gcd(-4, 6).0, eq(2));
+        expect_that!(mod_inverse(3, 11), some(eq(4)));
+        expect_that!(mod_inverse(-3, 11), some(eq(7)));
+        expect_that!(mod_inverse(2, 4), none());
+    }
+
+    #[googletest::test]
+    fn computes_modular_powers() {
+        expect_that!(mod_pow(2, 100, 1_000_000_007), eq(976371285));
+        expect_that!(mod_pow(3, 10_u128.pow(18), 998244353), eq(865857325));
+        expect_that!(mod_pow(5, 0, 1), eq(0));
+    }
+
+    #[googletest::test]
+    fn solves_chinese_remainder_theorem() {
+        let coprime = [
+            Congruence::new(2, 3),
+            Congruence::new(3, 5),
+            Congruence::new(2, 7),
+        ];
+        expect_that!(crt(coprime), some(eq(Congruence::new(23, 105))));
+
+        let shared_factor = [Congruence::new(1, 4), Congruence::new(3, 6)];
+        expect_that!(crt(shared_factor), some(eq(Congruence::new(9, 12))));
+
+        let contradictory = [Congruence::new(1, 4), Congruence::new(2, 6)];
+        expect_that!(crt(contradictory), none());
+    }
+
+    #[googletest::test]
+    fn finds_first_solution_after_offset() {
+        let congruence = Congruence::new(9, 12);
+        expect_that!(congruence.first_at_least(0), eq(9));
+        expect_that!(congruence.first_at_least(9), eq(9));
+        expect_that!(congruence.first_at_least(10), eq(21));
+        expect_that!(congruence.first_at_least(-20), eq(-15));
+    }
+}
@@ -0,0 +1,142 @@
+use num::{BigInt, BigRational, One, Zero};
+
+// Differences between consecutive values, one shorter than `values`.
+pub fn differences(values: &[BigInt]) -> Vec<BigInt> {
+    values.windows(2).map(|pair| &pair[1] - &pair[0]).collect()
+}
+
+// First value of each row of repeated differences, stopping once a row is all zeros.
+fn leading_differences(values: &[BigInt]) -> Vec<BigInt> {
+    let mut leading = vec![];
+    let mut row = values.to_vec();
+    while !row.is_empty() && !row.iter().all(Zero::is_zero) {
+        leading.push(row[0].clone());
+        row = differences(&row);
+    }
+    leading
+}
+
+// Smallest d such that values sampled at consecutive integers fit a degree-d polynomial, or None
+// if there aren't enough samples to confirm any degree. Confirming degree d needs d + 2 samples.
+pub fn minimal_degree(values: &[BigInt]) -> Option<usize> {
+    let mut row = values.to_vec();
+    for degree in 0.. {
+        if row.len() < 2 {
+            return None;
+        }
+        if row.iter().all(|value| *value == row[0]) {
+            return Some(degree);
+        }
+        row = differences(&row);
+    }
+    unreachable!()
+}
+
+// Value at `x` of the lowest-degree polynomial through values sampled at 0, 1, 2, ..., by
+// Newton's forward difference formula. `x` may be negative or far beyond the samples.
+pub fn extrapolate(values: &[BigInt], x: &BigInt) -> BigInt {
+    let mut result = BigInt::zero();
+    // binomial(x, k), which stays an integer even for negative x.
+    let mut binomial = BigInt::one();
+    for (k, difference) in leading_differences(values).iter().enumerate() {
+        if k > 0 {
+            binomial = binomial * (x - BigInt::from(k - 1)) / BigInt::from(k);
+        }
+        result += &binomial * difference;
+    }
+    result
+}
+
+// Value just after the last sample.
+pub fn next_value(values: &[BigInt]) -> BigInt {
+    extrapolate(values, &BigInt::from(values.len()))
+}
+
+// Value just before the first sample.
+pub fn previous_value(values: &[BigInt]) -> BigInt {
+    extrapolate(values, &BigInt::from(-1))
+}
+
+// Value at `x` of the polynomial through `points`, which may be spaced arbitrarily.
+// Panics if two points share an x coordinate.
+pub fn lagrange_interpolate(points: &[(BigRational, BigRational)], x: &BigRational) -> BigRational {
+    let mut result = BigRational::zero();
+    for (i, (x_i, y_i)) in points.iter().enumerate() {
+        let mut term = y_i.clone();
+        for (j, (x_j, _)) in points.iter().enumerate() {
+            if i != j {
+                if x_i == x_j {
+                    panic!("Duplicate x coordinate {x_i} in interpolation points");
+                }
+                term = term * (x - x_j) / (x_i - x_j);
+            }
+        }
+        result += term;
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use googletest::prelude::*;
+
+    fn big(values: &[i64]) -> Vec<BigInt> {
+        values.iter().map(|&v| BigInt::from(v)).collect()
+    }
+
+    fn rational(n: i64) -> BigRational {
+        BigRational::from_integer(BigInt::from(n))
+    }
+
+    #[googletest::test]
+    fn extrapolates_in_both_directions() {
+        expect_that!(
+            next_value(&big(&[0, 3, 6, 9, 12, 15])),
+            eq(BigInt::from(18))
+        );
+        expect_that!(
+            next_value(&big(&[10, 13, 16, 21, 30, 45])),
+            eq(BigInt::from(68))
+        );
+        expect_that!(
+            previous_value(&big(&[10, 13, 16, 21, 30, 45])),
+            eq(BigInt::from(5))
+        );
+        expect_that!(next_value(&big(&[0, 0, 0])), eq(BigInt::zero()));
+    }
+
+    #[googletest::test]
+    fn extrapolates_far_without_overflow() {
+        // 2x^3 - x + 7 at x = 10^12 overflows i64 but not BigInt.
+        let samples = big(&[7, 8, 21, 58]);
+        let x = BigInt::from(10_i64.pow(12));
+        let expected = BigInt::from(2) * x.pow(3) - &x + 7;
+        expect_that!(extrapolate(&samples, &x), eq(expected));
+    }
+
+    #[googletest::test]
+    fn detects_minimal_degree() {
+        expect_that!(minimal_degree(&big(&[5, 5])), some(eq(0)));
+        expect_that!(minimal_degree(&big(&[1, 4, 9, 16])), some(eq(2)));
+        expect_that!(minimal_degree(&big(&[1, 4, 9])), none());
+        expect_that!(minimal_degree(&big(&[7])), none());
+    }
+
+    #[googletest::test]
+    fn interpolates_exactly_through_arbitrary_points() {
+        // y = x^2 / 2 through unevenly spaced points, evaluated between them.
+        let points: Vec<_> = [-3, 1, 4]
+            .iter()
+            .map(|&x| (rational(x), rational(x * x) / rational(2)))
+            .collect();
+        expect_that!(
+            lagrange_interpolate(&points, &rational(3)),
+            eq(rational(9) / rational(2))
+        );
+        expect_that!(
+            lagrange_interpolate(&points, &(rational(1) / rational(3))),
+            eq(rational(1) / rational(18))
+        );
+    }
+}
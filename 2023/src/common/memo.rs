@@ -0,0 +1,137 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+// Storage for computed values; `Memo` works with any of these.
+pub trait Cache<K, V> {
+    fn get(&self, key: &K) -> Option<&V>;
+    fn insert(&mut self, key: K, value: V);
+}
+
+impl<K: Eq + Hash, V> Cache<K, V> for HashMap<K, V> {
+    fn get(&self, key: &K) -> Option<&V> {
+        HashMap::get(self, key)
+    }
+
+    fn insert(&mut self, key: K, value: V) {
+        HashMap::insert(self, key, value);
+    }
+}
+
+// A cache backed by a flat array, for keys that map onto a small dense range of indices.
+pub struct DenseCache<K, V> {
+    index: fn(&K) -> usize,
+    values: Vec<Option<V>>,
+}
+
+impl<K, V> DenseCache<K, V> {
+    // `index` must map every key that'll be looked up into 0..len.
+    pub fn new(len: usize, index: fn(&K) -> usize) -> Self {
+        Self {
+            index,
+            values: std::iter::repeat_with(|| None).take(len).collect(),
+        }
+    }
+}
+
+impl<K, V> Cache<K, V> for DenseCache<K, V> {
+    fn get(&self, key: &K) -> Option<&V> {
+        self.values[(self.index)(key)].as_ref()
+    }
+
+    fn insert(&mut self, key: K, value: V) {
+        let index = (self.index)(&key);
+        self.values[index] = Some(value);
+    }
+}
+
+// Memoizes a recursive function. Compute each value through `get_or_compute`, passing the memo
+// back into the recursive calls:
+//
+//     fn fib(memo: &mut Memo<u64, u64>, n: u64) -> u64 {
+//         memo.get_or_compute(n, |memo| if n < 2 { n } else { fib(memo, n - 1) + fib(memo, n - 2) })
+//     }
+pub struct Memo<K, V, C = HashMap<K, V>> {
+    cache: C,
+    _types: std::marker::PhantomData<(K, V)>,
+}
+
+impl<K: Eq + Hash, V> Memo<K, V> {
+    pub fn new() -> Self {
+        Self::with_cache(HashMap::new())
+    }
+}
+
+impl<K: Eq + Hash, V> Default for Memo<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, V, C: Cache<K, V>> Memo<K, V, C> {
+    pub fn with_cache(cache: C) -> Self {
+        Self {
+            cache,
+            _types: std::marker::PhantomData,
+        }
+    }
+
+    // The cached value for `key`, computing and caching it first if needed.
+    pub fn get_or_compute<F>(&mut self, key: K, compute: F) -> V
+    where
+        V: Clone,
+        F: FnOnce(&mut Self) -> V,
+    {
+        if let Some(value) = self.cache.get(&key) {
+            return value.clone();
+        }
+        let value = compute(self);
+        self.cache.insert(key, value.clone());
+        return value;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use googletest::prelude::*;
+
+    fn fib<C: Cache<u64, u64>>(memo: &mut Memo<u64, u64, C>, n: u64) -> u64 {
+        memo.get_or_compute(n, |memo| {
+            if n < 2 {
+                n
+            } else {
+                fib(memo, n - 1) + fib(memo, n - 2)
+            }
+        })
+    }
+
+    // Lattice paths from (0, 0) to (i, j) moving right or down.
+    fn paths<C: Cache<(usize, usize), u64>>(
+        memo: &mut Memo<(usize, usize), u64, C>,
+        i: usize,
+        j: usize,
+    ) -> u64 {
+        memo.get_or_compute((i, j), |memo| {
+            if i == 0 || j == 0 {
+                1
+            } else {
+                paths(memo, i - 1, j) + paths(memo, i, j - 1)
+            }
+        })
+    }
+
+    #[googletest::test]
+    fn memoizes_with_hash_map() {
+        expect_that!(fib(&mut Memo::new(), 90), eq(2880067194370816120));
+        expect_that!(paths(&mut Memo::new(), 16, 16), eq(601080390));
+    }
+
+    #[googletest::test]
+    fn memoizes_with_dense_cache() {
+        let mut memo = Memo::with_cache(DenseCache::new(91, |&n| n as usize));
+        expect_that!(fib(&mut memo, 90), eq(2880067194370816120));
+
+        let mut memo = Memo::with_cache(DenseCache::new(17 * 17, |&(i, j)| i * 17 + j));
+        expect_that!(paths(&mut memo, 16, 16), eq(601080390));
+    }
+}
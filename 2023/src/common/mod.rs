@@ -1,4 +1,16 @@
-pub mod coordinates;
+pub mod animation;
+pub mod cuboid;
+pub mod cycle;
+pub mod debug;
 pub mod direction;
+pub mod dot;
+pub mod geometry;
+pub mod graph;
 pub mod grid;
-pub mod position;
+pub mod intern;
+pub mod interval;
+pub mod math;
+pub mod memo;
+pub mod point;
+pub mod position_set;
+pub mod search;
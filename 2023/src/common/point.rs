@@ -0,0 +1,272 @@
+use super::grid::Grid;
+use crate::common::direction::{Direction, Direction8, HexDirection};
+use num::{CheckedAdd, CheckedSub, Integer, NumCast, Signed};
+use std::ops::{Add, Mul, Sub};
+
+// A 2D point as (row, column), with rows increasing southwards and columns increasing eastwards.
+// Ordering is row-major.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Hash, Default)]
+pub struct Point<T>(pub T, pub T);
+
+// Unsigned points index into grids.
+pub type Position = Point<usize>;
+
+// Signed points can wander off in any direction, e.g. when tracing a path from the origin.
+pub type Coordinates = Point<i64>;
+
+impl<T> Point<T>
+where
+    T: Integer + Copy,
+{
+    // Get the next point by taking a step in the given direction, or None if it over/underflows.
+    pub fn step(&self, direction: Direction) -> Option<Self>
+    where
+        T: CheckedAdd + CheckedSub,
+    {
+        self.step_by(direction, T::one())
+    }
+
+    // Get the next point by taking a step in the given direction by the size.
+    pub fn step_by(&self, direction: Direction, step_size: T) -> Option<Self>
+    where
+        T: CheckedAdd + CheckedSub,
+    {
+        match direction {
+            Direction::North => Some(Self(self.0.checked_sub(&step_size)?, self.1)),
+            Direction::South => Some(Self(self.0.checked_add(&step_size)?, self.1)),
+            Direction::East => Some(Self(self.0, self.1.checked_add(&step_size)?)),
+            Direction::West => Some(Self(self.0, self.1.checked_sub(&step_size)?)),
+        }
+    }
+
+    // Step in one of eight directions, or None if it over/underflows.
+    pub fn step8(&self, direction: Direction8) -> Option<Self>
+    where
+        T: CheckedAdd + CheckedSub,
+    {
+        let step = |value: T, offset: i32| match offset {
+            -1 => value.checked_sub(&T::one()),
+            1 => value.checked_add(&T::one()),
+            _ => Some(value),
+        };
+        let (di, dj) = direction.offset();
+        Some(Self(step(self.0, di)?, step(self.1, dj)?))
+    }
+
+    pub fn manhattan_distance(&self, other: &Self) -> T {
+        abs_difference(self.0, other.0) + abs_difference(self.1, other.1)
+    }
+
+    pub fn chebyshev_distance(&self, other: &Self) -> T {
+        abs_difference(self.0, other.0).max(abs_difference(self.1, other.1))
+    }
+
+    // Convert to a point of another integer type, or None if a component doesn't fit.
+    pub fn cast<U>(&self) -> Option<Point<U>>
+    where
+        T: NumCast,
+        U: NumCast,
+    {
+        Some(Point(U::from(self.0)?, U::from(self.1)?))
+    }
+}
+
+impl<T> Point<T>
+where
+    T: Integer + Signed + Copy,
+{
+    // Unit vector pointing in the given direction.
+    pub fn unit(direction: Direction) -> Self {
+        match direction {
+            Direction::North => Self(-T::one(), T::zero()),
+            Direction::South => Self(T::one(), T::zero()),
+            Direction::East => Self(T::zero(), T::one()),
+            Direction::West => Self(T::zero(), -T::one()),
+        }
+    }
+}
+
+fn abs_difference<T: Integer + Copy>(a: T, b: T) -> T {
+    if a > b {
+        a - b
+    } else {
+        b - a
+    }
+}
+
+impl<T: Add<Output = T>> Add for Point<T> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self(self.0 + other.0, self.1 + other.1)
+    }
+}
+
+impl<T: Sub<Output = T>> Sub for Point<T> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self(self.0 - other.0, self.1 - other.1)
+    }
+}
+
+impl<T: Mul<Output = T> + Copy> Mul<T> for Point<T> {
+    type Output = Self;
+
+    fn mul(self, scalar: T) -> Self {
+        Self(self.0 * scalar, self.1 * scalar)
+    }
+}
+
+// A 3D point as (x, y, z).
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Hash, Default)]
+pub struct Point3<T>(pub T, pub T, pub T);
+
+impl<T> Point3<T>
+where
+    T: Integer + Copy,
+{
+    pub fn manhattan_distance(&self, other: &Self) -> T {
+        abs_difference(self.0, other.0)
+            + abs_difference(self.1, other.1)
+            + abs_difference(self.2, other.2)
+    }
+}
+
+impl<T: Add<Output = T>> Add for Point3<T> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self(self.0 + other.0, self.1 + other.1, self.2 + other.2)
+    }
+}
+
+impl<T: Sub<Output = T>> Sub for Point3<T> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self(self.0 - other.0, self.1 - other.1, self.2 - other.2)
+    }
+}
+
+impl<T: Mul<Output = T> + Copy> Mul<T> for Point3<T> {
+    type Output = Self;
+
+    fn mul(self, scalar: T) -> Self {
+        Self(self.0 * scalar, self.1 * scalar, self.2 * scalar)
+    }
+}
+
+// Axial (q, r) coordinates on a hex grid; see `HexDirection`.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Hash, Default)]
+pub struct Axial(pub i64, pub i64);
+
+impl Axial {
+    pub fn step(&self, direction: HexDirection) -> Self {
+        let (dq, dr) = direction.offset();
+        Self(self.0 + dq as i64, self.1 + dr as i64)
+    }
+
+    // Number of steps between two hexes.
+    pub fn distance(&self, other: &Self) -> i64 {
+        let dq = self.0 - other.0;
+        let dr = self.1 - other.1;
+        (dq.abs() + dr.abs() + (dq + dr).abs()) / 2
+    }
+
+    pub fn neighbors(&self) -> impl Iterator<Item = Axial> + '_ {
+        HexDirection::ALL.iter().map(|&d| self.step(d))
+    }
+}
+
+impl Position {
+    pub fn step_within_grid<T>(&self, direction: Direction, grid: &Grid<T>) -> Option<Self> {
+        self.step_by_within_grid(direction, 1, grid)
+    }
+
+    pub fn step_by_within_grid<T>(
+        &self,
+        direction: Direction,
+        step_size: usize,
+        grid: &Grid<T>,
+    ) -> Option<Self> {
+        let next_position = self.step_by(direction, step_size)?;
+        if next_position.is_in_bounds(grid) {
+            Some(next_position)
+        } else {
+            None
+        }
+    }
+
+    pub fn is_in_bounds<T>(&self, grid: &Grid<T>) -> bool {
+        if grid.is_empty() {
+            println!("Position::is_in_bounds: received empty grid!");
+            return false;
+        }
+        self.0 < grid.len() && self.1 < grid[0].len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use googletest::prelude::*;
+
+    #[googletest::test]
+    fn steps_unsigned_and_signed_points() {
+        expect_that!(Position::default().step(Direction::North), none());
+        expect_that!(
+            Point(1_usize, 1).step_by(Direction::West, 1),
+            some(eq(Point(1, 0)))
+        );
+        expect_that!(
+            Coordinates::default().step_by(Direction::North, 3),
+            some(eq(Point(-3, 0)))
+        );
+        expect_that!(
+            Coordinates::default() + Coordinates::unit(Direction::West) * 4,
+            eq(Point(0, -4))
+        );
+    }
+
+    #[googletest::test]
+    fn computes_distances() {
+        expect_that!(Point(1_usize, 5).manhattan_distance(&Point(4, 2)), eq(6));
+        expect_that!(Point(1_usize, 5).chebyshev_distance(&Point(4, 2)), eq(3));
+        expect_that!(Point(-1_i64, 5).manhattan_distance(&Point(4, -2)), eq(12));
+    }
+
+    #[googletest::test]
+    fn converts_between_signed_and_unsigned() {
+        expect_that!(Point(2_usize, 3).cast::<i64>(), some(eq(Point(2, 3))));
+        expect_that!(Point(-1_i64, 3).cast::<usize>(), none());
+        expect_that!(Point(0, 1) < Point(1, 0), eq(true));
+    }
+
+    #[googletest::test]
+    fn steps_diagonally() {
+        expect_that!(
+            Point(1_usize, 1).step8(Direction8::NorthWest),
+            some(eq(Point(0, 0)))
+        );
+        expect_that!(Point(0_usize, 1).step8(Direction8::NorthEast), none());
+        expect_that!(
+            Point(0_i64, 0).step8(Direction8::SouthWest),
+            some(eq(Point(1, -1)))
+        );
+    }
+
+    #[googletest::test]
+    fn walks_hex_grid() {
+        let origin = Axial::default();
+        let target = origin
+            .step(HexDirection::NorthEast)
+            .step(HexDirection::NorthEast)
+            .step(HexDirection::South);
+        expect_that!(target.distance(&origin), eq(2));
+        expect_that!(
+            origin.neighbors().all(|n| n.distance(&origin) == 1),
+            eq(true)
+        );
+    }
+}
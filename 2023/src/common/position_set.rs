@@ -0,0 +1,302 @@
+use crate::common::direction::Direction;
+use crate::common::grid::Grid;
+use crate::common::point::{Point, Position};
+
+// Keys that can be densely indexed within a grid: a position, plus an optional extra dimension
+// such as the heading at that position.
+pub trait PositionKey: Copy {
+    // Number of distinct keys per position.
+    const LAYERS: usize;
+
+    fn position(&self) -> Position;
+    fn layer(&self) -> usize;
+    fn from_parts(position: Position, layer: usize) -> Self;
+}
+
+impl PositionKey for Position {
+    const LAYERS: usize = 1;
+
+    fn position(&self) -> Position {
+        *self
+    }
+
+    fn layer(&self) -> usize {
+        0
+    }
+
+    fn from_parts(position: Position, _layer: usize) -> Self {
+        position
+    }
+}
+
+const DIRECTIONS: [Direction; 4] = [
+    Direction::North,
+    Direction::East,
+    Direction::South,
+    Direction::West,
+];
+
+impl PositionKey for (Position, Direction) {
+    const LAYERS: usize = 4;
+
+    fn position(&self) -> Position {
+        self.0
+    }
+
+    fn layer(&self) -> usize {
+        DIRECTIONS.iter().position(|&d| d == self.1).unwrap()
+    }
+
+    fn from_parts(position: Position, layer: usize) -> Self {
+        (position, DIRECTIONS[layer])
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Dimensions {
+    num_rows: usize,
+    num_columns: usize,
+}
+
+impl Dimensions {
+    fn of_grid<T>(grid: &Grid<T>) -> Self {
+        Self {
+            num_rows: grid.len(),
+            num_columns: grid.first().map_or(0, |row| row.len()),
+        }
+    }
+
+    fn capacity<K: PositionKey>(&self) -> usize {
+        self.num_rows * self.num_columns * K::LAYERS
+    }
+
+    // Panics if the key's position is out of bounds.
+    fn index<K: PositionKey>(&self, key: &K) -> usize {
+        let position = key.position();
+        assert!(
+            position.0 < self.num_rows && position.1 < self.num_columns,
+            "{position:?} is out of bounds"
+        );
+        (position.0 * self.num_columns + position.1) * K::LAYERS + key.layer()
+    }
+
+    fn key<K: PositionKey>(&self, index: usize) -> K {
+        let (cell, layer) = (index / K::LAYERS, index % K::LAYERS);
+        K::from_parts(
+            Point(cell / self.num_columns, cell % self.num_columns),
+            layer,
+        )
+    }
+}
+
+// A set of positions (or position keys) within a grid, stored as a bitset for O(1) access
+// without hashing.
+#[derive(Clone, Debug)]
+pub struct PositionSet<K: PositionKey = Position> {
+    dimensions: Dimensions,
+    bits: Vec<u64>,
+    len: usize,
+    marker: std::marker::PhantomData<K>,
+}
+
+impl<K: PositionKey> PositionSet<K> {
+    pub fn new(num_rows: usize, num_columns: usize) -> Self {
+        Self::with_dimensions(Dimensions {
+            num_rows,
+            num_columns,
+        })
+    }
+
+    pub fn for_grid<T>(grid: &Grid<T>) -> Self {
+        Self::with_dimensions(Dimensions::of_grid(grid))
+    }
+
+    fn with_dimensions(dimensions: Dimensions) -> Self {
+        Self {
+            dimensions,
+            bits: vec![0; dimensions.capacity::<K>().div_ceil(64)],
+            len: 0,
+            marker: std::marker::PhantomData,
+        }
+    }
+
+    // Returns whether the key was newly inserted.
+    pub fn insert(&mut self, key: K) -> bool {
+        let index = self.dimensions.index(&key);
+        let (word, bit) = (index / 64, 1 << (index % 64));
+        let is_new = self.bits[word] & bit == 0;
+        self.bits[word] |= bit;
+        if is_new {
+            self.len += 1;
+        }
+        is_new
+    }
+
+    // Returns whether the key was present.
+    pub fn remove(&mut self, key: &K) -> bool {
+        let index = self.dimensions.index(key);
+        let (word, bit) = (index / 64, 1 << (index % 64));
+        let was_present = self.bits[word] & bit != 0;
+        self.bits[word] &= !bit;
+        if was_present {
+            self.len -= 1;
+        }
+        was_present
+    }
+
+    pub fn contains(&self, key: &K) -> bool {
+        let index = self.dimensions.index(key);
+        self.bits[index / 64] & (1 << (index % 64)) != 0
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn clear(&mut self) {
+        self.bits.fill(0);
+        self.len = 0;
+    }
+
+    // Keys in row-major order.
+    pub fn iter(&self) -> impl Iterator<Item = K> + '_ {
+        self.bits
+            .iter()
+            .enumerate()
+            .flat_map(move |(word_idx, &word)| {
+                (0..64)
+                    .filter(move |bit| word & (1 << bit) != 0)
+                    .map(move |bit| self.dimensions.key(word_idx * 64 + bit))
+            })
+    }
+}
+
+// A map from positions (or position keys) within a grid to values, stored densely for O(1)
+// access without hashing.
+#[derive(Clone, Debug)]
+pub struct PositionMap<K: PositionKey, T> {
+    dimensions: Dimensions,
+    values: Vec<Option<T>>,
+    len: usize,
+    marker: std::marker::PhantomData<K>,
+}
+
+impl<K: PositionKey, T> PositionMap<K, T> {
+    pub fn new(num_rows: usize, num_columns: usize) -> Self {
+        Self::with_dimensions(Dimensions {
+            num_rows,
+            num_columns,
+        })
+    }
+
+    pub fn for_grid<U>(grid: &Grid<U>) -> Self {
+        Self::with_dimensions(Dimensions::of_grid(grid))
+    }
+
+    fn with_dimensions(dimensions: Dimensions) -> Self {
+        Self {
+            dimensions,
+            values: (0..dimensions.capacity::<K>()).map(|_| None).collect(),
+            len: 0,
+            marker: std::marker::PhantomData,
+        }
+    }
+
+    // Returns the previous value, if any.
+    pub fn insert(&mut self, key: K, value: T) -> Option<T> {
+        let previous = self.values[self.dimensions.index(&key)].replace(value);
+        if previous.is_none() {
+            self.len += 1;
+        }
+        previous
+    }
+
+    pub fn remove(&mut self, key: &K) -> Option<T> {
+        let previous = self.values[self.dimensions.index(key)].take();
+        if previous.is_some() {
+            self.len -= 1;
+        }
+        previous
+    }
+
+    pub fn get(&self, key: &K) -> Option<&T> {
+        self.values[self.dimensions.index(key)].as_ref()
+    }
+
+    pub fn get_mut(&mut self, key: &K) -> Option<&mut T> {
+        self.values[self.dimensions.index(key)].as_mut()
+    }
+
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.get(key).is_some()
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    // Entries in row-major order.
+    pub fn iter(&self) -> impl Iterator<Item = (K, &T)> + '_ {
+        self.values
+            .iter()
+            .enumerate()
+            .filter_map(move |(i, value)| Some((self.dimensions.key(i), value.as_ref()?)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use googletest::prelude::*;
+
+    #[googletest::test]
+    fn inserts_and_removes_positions() {
+        let mut set = PositionSet::new(3, 30);
+        expect_that!(set.insert(Point(2, 29)), eq(true));
+        expect_that!(set.insert(Point(2, 29)), eq(false));
+        expect_that!(set.insert(Point(0, 1)), eq(true));
+        expect_that!(set.contains(&Point(2, 29)), eq(true));
+        expect_that!(set.contains(&Point(1, 29)), eq(false));
+        expect_that!(set.len(), eq(2));
+        expect_that!(
+            set.iter().collect::<Vec<_>>(),
+            eq(vec![Point(0, 1), Point(2, 29)])
+        );
+
+        expect_that!(set.remove(&Point(0, 1)), eq(true));
+        expect_that!(set.len(), eq(1));
+    }
+
+    #[googletest::test]
+    fn tracks_positions_with_directions() {
+        let mut set = PositionSet::<(Position, Direction)>::new(2, 2);
+        set.insert((Point(1, 1), Direction::West));
+        expect_that!(set.contains(&(Point(1, 1), Direction::West)), eq(true));
+        expect_that!(set.contains(&(Point(1, 1), Direction::East)), eq(false));
+        expect_that!(
+            set.iter().collect::<Vec<_>>(),
+            eq(vec![(Point(1, 1), Direction::West)])
+        );
+    }
+
+    #[googletest::test]
+    fn maps_positions_to_values() {
+        let mut map = PositionMap::<Position, u32>::new(2, 2);
+        expect_that!(map.insert(Point(0, 1), 5), none());
+        expect_that!(map.insert(Point(0, 1), 6), some(eq(5)));
+        *map.get_mut(&Point(0, 1)).unwrap() += 1;
+        expect_that!(map.get(&Point(0, 1)), some(eq(&7)));
+        expect_that!(map.get(&Point(1, 1)), none());
+        expect_that!(map.len(), eq(1));
+        expect_that!(map.remove(&Point(0, 1)), some(eq(7)));
+        expect_that!(map.is_empty(), eq(true));
+    }
+}
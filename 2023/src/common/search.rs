@@ -0,0 +1,380 @@
+use crate::common::position_set::{PositionKey, PositionMap};
+use num::Zero;
+use priority_queue::PriorityQueue;
+use std::cmp::Reverse;
+use std::collections::{HashMap, VecDeque};
+use std::hash::Hash;
+use std::ops::Add;
+
+// Storage for what a search knows about each state; `HashMap` works for any hashable state, and
+// `PositionMap` keeps searches over grid states free of hashing.
+pub trait StateMap<S, V> {
+    fn get(&self, state: &S) -> Option<&V>;
+    fn insert(&mut self, state: S, value: V);
+    fn values<'a>(&'a self) -> impl Iterator<Item = &'a V>
+    where
+        V: 'a;
+}
+
+impl<S: Eq + Hash, V> StateMap<S, V> for HashMap<S, V> {
+    fn get(&self, state: &S) -> Option<&V> {
+        HashMap::get(self, state)
+    }
+
+    fn insert(&mut self, state: S, value: V) {
+        HashMap::insert(self, state, value);
+    }
+
+    fn values<'a>(&'a self) -> impl Iterator<Item = &'a V>
+    where
+        V: 'a,
+    {
+        HashMap::values(self)
+    }
+}
+
+impl<K: PositionKey, V> StateMap<K, V> for PositionMap<K, V> {
+    fn get(&self, state: &K) -> Option<&V> {
+        PositionMap::get(self, state)
+    }
+
+    fn insert(&mut self, state: K, value: V) {
+        PositionMap::insert(self, state, value);
+    }
+
+    fn values<'a>(&'a self) -> impl Iterator<Item = &'a V>
+    where
+        V: 'a,
+    {
+        self.iter().map(|(_, value)| value)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchResult<S, C> {
+    pub cost: C,
+    // States from a start to the goal, inclusive.
+    pub path: Vec<S>,
+}
+
+// Cheapest path from any of the starting states to a goal state, where `successors` yields
+// (next state, step cost) pairs. Costs must be non-negative.
+pub fn dijkstra<S, C, I, FN, IN, FG>(
+    starts: I,
+    successors: FN,
+    is_goal: FG,
+) -> Option<SearchResult<S, C>>
+where
+    S: Clone + Eq + Hash,
+    C: Copy + Ord + Zero + Add<Output = C>,
+    I: IntoIterator<Item = S>,
+    FN: FnMut(&S) -> IN,
+    IN: IntoIterator<Item = (S, C)>,
+    FG: FnMut(&S) -> bool,
+{
+    a_star(starts, successors, |_| C::zero(), is_goal)
+}
+
+// Like `dijkstra`, guided by a heuristic that estimates the remaining cost to a goal. The
+// result is optimal when the heuristic never overestimates.
+pub fn a_star<S, C, I, FN, IN, FH, FG>(
+    starts: I,
+    successors: FN,
+    heuristic: FH,
+    is_goal: FG,
+) -> Option<SearchResult<S, C>>
+where
+    S: Clone + Eq + Hash,
+    C: Copy + Ord + Zero + Add<Output = C>,
+    I: IntoIterator<Item = S>,
+    FN: FnMut(&S) -> IN,
+    IN: IntoIterator<Item = (S, C)>,
+    FH: FnMut(&S) -> C,
+    FG: FnMut(&S) -> bool,
+{
+    a_star_with_store(starts, successors, heuristic, is_goal, HashMap::new())
+}
+
+// Like `a_star`, keeping each state's best known cost, and the state it was reached from, in
+// `best`, which should start out empty.
+pub fn a_star_with_store<S, C, I, FN, IN, FH, FG, M>(
+    starts: I,
+    mut successors: FN,
+    mut heuristic: FH,
+    mut is_goal: FG,
+    mut best: M,
+) -> Option<SearchResult<S, C>>
+where
+    S: Clone + Eq + Hash,
+    C: Copy + Ord + Zero + Add<Output = C>,
+    I: IntoIterator<Item = S>,
+    FN: FnMut(&S) -> IN,
+    IN: IntoIterator<Item = (S, C)>,
+    FH: FnMut(&S) -> C,
+    FG: FnMut(&S) -> bool,
+    M: StateMap<S, (C, Option<S>)>,
+{
+    let mut next_states: PriorityQueue<S, Reverse<C>> = PriorityQueue::new();
+
+    for start in starts {
+        let priority = heuristic(&start);
+        best.insert(start.clone(), (C::zero(), None));
+        next_states.push(start, Reverse(priority));
+    }
+
+    while let Some((state, _)) = next_states.pop() {
+        let cost = best.get(&state).unwrap().0;
+        if is_goal(&state) {
+            return Some(SearchResult {
+                cost,
+                path: reconstruct_path(&best, state),
+            });
+        }
+
+        for (next_state, step_cost) in successors(&state) {
+            let next_cost = cost + step_cost;
+            if best
+                .get(&next_state)
+                .is_none_or(|&(known_cost, _)| next_cost < known_cost)
+            {
+                let priority = next_cost + heuristic(&next_state);
+                best.insert(next_state.clone(), (next_cost, Some(state.clone())));
+                next_states.push_increase(next_state, Reverse(priority));
+            }
+        }
+    }
+
+    None
+}
+
+fn reconstruct_path<S: Clone, C>(best: &impl StateMap<S, (C, Option<S>)>, goal: S) -> Vec<S> {
+    let mut path = vec![goal];
+    while let Some(previous) = &best.get(path.last().unwrap()).unwrap().1 {
+        path.push(previous.clone());
+    }
+    path.reverse();
+    path
+}
+
+// Breadth-first search over unweighted successors, from one or more sources.
+pub struct Bfs<S, M = HashMap<S, usize>> {
+    sources: Vec<S>,
+    max_depth: Option<usize>,
+    distances: M,
+}
+
+#[derive(Debug, Clone)]
+pub struct BfsResult<S, M = HashMap<S, usize>> {
+    // Distance to every state visited, from the nearest source.
+    pub distances: M,
+    // The goal state that ended the search early, if any.
+    pub goal: Option<S>,
+}
+
+impl<S: Clone + Eq + Hash> Bfs<S> {
+    pub fn new<I: IntoIterator<Item = S>>(sources: I) -> Self {
+        Self::with_store(sources, HashMap::new())
+    }
+}
+
+impl<S: Clone, M: StateMap<S, usize>> Bfs<S, M> {
+    // Records distances in `distances`, which should start out empty, e.g. a `PositionMap` for
+    // searches within a grid.
+    pub fn with_store<I: IntoIterator<Item = S>>(sources: I, distances: M) -> Self {
+        Self {
+            sources: sources.into_iter().collect(),
+            max_depth: None,
+            distances,
+        }
+    }
+
+    // Don't expand past states at this distance from the sources.
+    pub fn with_max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = Some(max_depth);
+        self
+    }
+
+    // Visit every reachable state.
+    pub fn run<FN, IN>(self, successors: FN) -> BfsResult<S, M>
+    where
+        FN: FnMut(&S) -> IN,
+        IN: IntoIterator<Item = S>,
+    {
+        self.run_until(successors, |_| false)
+    }
+
+    // Visit reachable states in order of distance, stopping at the first goal state.
+    pub fn run_until<FN, IN, FG>(self, mut successors: FN, mut is_goal: FG) -> BfsResult<S, M>
+    where
+        FN: FnMut(&S) -> IN,
+        IN: IntoIterator<Item = S>,
+        FG: FnMut(&S) -> bool,
+    {
+        let mut distances = self.distances;
+        let mut next_states = VecDeque::new();
+        for source in &self.sources {
+            if distances.get(source).is_none() {
+                distances.insert(source.clone(), 0);
+                next_states.push_back((source.clone(), 0));
+            }
+        }
+
+        while let Some((state, distance)) = next_states.pop_front() {
+            if is_goal(&state) {
+                return BfsResult {
+                    distances,
+                    goal: Some(state),
+                };
+            }
+            if self
+                .max_depth
+                .is_some_and(|max_depth| distance >= max_depth)
+            {
+                continue;
+            }
+            for next_state in successors(&state) {
+                if distances.get(&next_state).is_none() {
+                    distances.insert(next_state.clone(), distance + 1);
+                    next_states.push_back((next_state, distance + 1));
+                }
+            }
+        }
+
+        BfsResult {
+            distances,
+            goal: None,
+        }
+    }
+}
+
+impl<S, M: StateMap<S, usize>> BfsResult<S, M> {
+    pub fn distance(&self, state: &S) -> Option<usize> {
+        self.distances.get(state).copied()
+    }
+
+    pub fn goal_distance(&self) -> Option<usize> {
+        self.distance(self.goal.as_ref()?)
+    }
+
+    pub fn max_distance(&self) -> Option<usize> {
+        self.distances.values().max().copied()
+    }
+
+    // Number of states whose distance has the given parity (0 for even, 1 for odd), e.g. the
+    // cells a walker can end on after an even number of steps when it can step back and forth.
+    pub fn count_with_parity(&self, parity: usize) -> usize {
+        self.distances
+            .values()
+            .filter(|&&distance| distance % 2 == parity % 2)
+            .count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::point::{Point, Position};
+    use googletest::prelude::*;
+
+    fn graph_successors(node: &char) -> Vec<(char, u32)> {
+        match node {
+            'a' => vec![('b', 1), ('c', 4)],
+            'b' => vec![('c', 1), ('d', 5)],
+            'c' => vec![('d', 1)],
+            _ => vec![],
+        }
+    }
+
+    #[googletest::test]
+    fn finds_cheapest_path() {
+        let result = dijkstra(['a'], graph_successors, |&n| n == 'd');
+        expect_that!(
+            result,
+            some(eq(SearchResult {
+                cost: 3,
+                path: vec!['a', 'b', 'c', 'd']
+            }))
+        );
+        expect_that!(dijkstra(['c'], graph_successors, |&n| n == 'a'), none());
+        expect_that!(
+            dijkstra(['a', 'c'], graph_successors, |&n| n == 'd').map(|r| r.path),
+            some(eq(vec!['c', 'd']))
+        );
+    }
+
+    #[googletest::test]
+    fn finds_shortest_grid_path_with_a_star() {
+        let goal: Position = Point(4, 4);
+        let result = a_star(
+            [Point(0, 0)],
+            |p: &Position| {
+                [Point(p.0 + 1, p.1), Point(p.0, p.1 + 1)]
+                    .into_iter()
+                    .filter(|n| n.0 <= 4 && n.1 <= 4)
+                    .map(|n| (n, 1))
+            },
+            |p| p.manhattan_distance(&goal),
+            |p| *p == goal,
+        )
+        .unwrap();
+        expect_that!(result.cost, eq(8));
+        expect_that!(result.path.len(), eq(9));
+    }
+
+    #[googletest::test]
+    fn searches_with_a_dense_store() {
+        let result = a_star_with_store(
+            [Point(0, 0)],
+            |p: &Position| {
+                [Point(p.0 + 1, p.1), Point(p.0, p.1 + 1)]
+                    .into_iter()
+                    .filter(|n| n.0 <= 2 && n.1 <= 2)
+                    .map(|n| (n, 1))
+            },
+            |_| 0,
+            |p| *p == Point(2, 2),
+            PositionMap::new(3, 3),
+        )
+        .unwrap();
+        expect_that!(result.cost, eq(4));
+        expect_that!(result.path.first(), some(eq(&Point(0, 0))));
+        expect_that!(result.path.last(), some(eq(&Point(2, 2))));
+    }
+
+    fn line_successors(n: &i32) -> Vec<i32> {
+        vec![n - 1, n + 1]
+    }
+
+    #[googletest::test]
+    fn bfs_computes_distances_from_multiple_sources() {
+        let result = Bfs::new([0, 10]).with_max_depth(3).run(line_successors);
+        expect_that!(result.distance(&3), some(eq(3)));
+        expect_that!(result.distance(&5), none());
+        expect_that!(result.distance(&7), some(eq(3)));
+        expect_that!(result.distances.len(), eq(14));
+        expect_that!(result.count_with_parity(0), eq(6));
+        expect_that!(result.count_with_parity(1), eq(8));
+        expect_that!(result.max_distance(), some(eq(3)));
+    }
+
+    #[googletest::test]
+    fn bfs_stops_at_goal() {
+        let result = Bfs::new([0]).run_until(line_successors, |&n| n == -4);
+        expect_that!(result.goal, some(eq(-4)));
+        expect_that!(result.goal_distance(), some(eq(4)));
+        expect_that!(result.distance(&5), none());
+    }
+
+    #[googletest::test]
+    fn bfs_records_distances_in_a_dense_store() {
+        let result = Bfs::with_store([Point(0, 0)], PositionMap::new(2, 3)).run(|p: &Position| {
+            [Point(p.0 + 1, p.1), Point(p.0, p.1 + 1)]
+                .into_iter()
+                .filter(|n| n.0 < 2 && n.1 < 3)
+                .collect::<Vec<_>>()
+        });
+        expect_that!(result.distance(&Point(1, 2)), some(eq(3)));
+        expect_that!(result.max_distance(), some(eq(3)));
+        expect_that!(result.count_with_parity(1), eq(3));
+    }
+}
@@ -1,4 +1,7 @@
+use crate::common::dot::DotGraph;
+use std::collections::BTreeMap;
 use std::fmt::Display;
+use std::str::FromStr;
 
 pub trait Day {
     fn part1(&self, _input: &str) -> Result<Box<dyn Display>, &str> {
@@ -7,4 +10,132 @@ pub trait Day {
     fn part2(&self, _input: &str) -> Result<Box<dyn Display>, &str> {
         return Err("Unimplemented!");
     }
+
+    // Puzzle constants this day reads from `Params`, with the values the puzzle asks for.
+    fn default_params(&self) -> Params {
+        return Params::new();
+    }
+    // Days with parameters override these and have `part1`/`part2` pass `default_params()`.
+    fn part1_with_params(&self, input: &str, _params: &Params) -> Result<Box<dyn Display>, &str> {
+        return self.part1(input);
+    }
+    fn part2_with_params(&self, input: &str, _params: &Params) -> Result<Box<dyn Display>, &str> {
+        return self.part2(input);
+    }
+
+    // The input drawn as a graph, for puzzles where that's its natural shape.
+    fn dot_graph(&self, _input: &str) -> Option<DotGraph> {
+        return None;
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ParamError {
+    MalformedAssignment(String),
+    UnknownParam(String),
+    InvalidValue { key: String, value: String },
+}
+
+impl Display for ParamError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParamError::MalformedAssignment(assignment) => {
+                write!(f, "expected key=value, got '{assignment}'")
+            }
+            ParamError::UnknownParam(key) => write!(f, "unknown parameter '{key}'"),
+            ParamError::InvalidValue { key, value } => {
+                write!(f, "invalid value '{value}' for parameter '{key}'")
+            }
+        }
+    }
+}
+
+// Named puzzle constants, such as how many times to unfold the input, stored as strings and
+// parsed on use so the runner can override them from the command line.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Params {
+    values: BTreeMap<String, String>,
+}
+
+impl Params {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with<T: ToString>(mut self, key: &str, value: T) -> Self {
+        self.values.insert(key.to_string(), value.to_string());
+        self
+    }
+
+    // Overrides an existing parameter from a "key=value" string.
+    pub fn set_from_assignment(&mut self, assignment: &str) -> Result<(), ParamError> {
+        let (key, value) = assignment
+            .split_once('=')
+            .ok_or_else(|| ParamError::MalformedAssignment(assignment.to_string()))?;
+        match self.values.get_mut(key.trim()) {
+            Some(existing) => {
+                *existing = value.trim().to_string();
+                Ok(())
+            }
+            None => Err(ParamError::UnknownParam(key.trim().to_string())),
+        }
+    }
+
+    pub fn get<T: FromStr>(&self, key: &str) -> Result<T, ParamError> {
+        let value = self
+            .values
+            .get(key)
+            .ok_or_else(|| ParamError::UnknownParam(key.to_string()))?;
+        value.parse().map_err(|_| ParamError::InvalidValue {
+            key: key.to_string(),
+            value: value.clone(),
+        })
+    }
+
+    pub fn get_or_panic<T: FromStr>(&self, key: &str) -> T {
+        self.get(key).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &String)> {
+        self.values.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use googletest::prelude::*;
+
+    #[googletest::test]
+    fn overrides_declared_params() {
+        let mut params = Params::new().with("unfold_factor", 5);
+        expect_that!(params.get::<usize>("unfold_factor"), ok(eq(5)));
+
+        expect_that!(params.set_from_assignment("unfold_factor=7"), ok(eq(())));
+        expect_that!(params.get::<usize>("unfold_factor"), ok(eq(7)));
+    }
+
+    #[googletest::test]
+    fn rejects_bad_params() {
+        let mut params = Params::new().with("num_cycles", 1000);
+        expect_that!(
+            params.set_from_assignment("num_cycles"),
+            err(eq(ParamError::MalformedAssignment(
+                "num_cycles".to_string()
+            )))
+        );
+        expect_that!(
+            params.set_from_assignment("num_cylces=3"),
+            err(eq(ParamError::UnknownParam("num_cylces".to_string())))
+        );
+
+        params.set_from_assignment("num_cycles=lots").unwrap();
+        expect_that!(
+            params.get::<u64>("num_cycles"),
+            err(eq(ParamError::InvalidValue {
+                key: "num_cycles".to_string(),
+                value: "lots".to_string()
+            }))
+        );
+    }
 }
@@ -1,5 +1,8 @@
 use crate::common::direction::Direction;
-use crate::common::position::Position;
+use crate::common::grid::GridParser;
+use crate::common::point::Position;
+use crate::common::position_set::PositionMap;
+use crate::common::search::Bfs;
 use crate::day::Day;
 use core::panic;
 use std::fmt::Display;
@@ -12,25 +15,18 @@ struct Maze {
     loop_markings: Vec<Vec<bool>>,
 }
 
-fn get_starting_position(maze: &Vec<Vec<char>>) -> Position {
-    for (i, line) in maze.iter().enumerate() {
-        for (j, c) in line.iter().enumerate() {
-            if *c == 'S' {
-                return Position(i, j);
-            }
-        }
-    }
-    panic!("Maze does not have a starting position!");
-}
-
 impl Maze {
     fn parse_from_input(input: &str) -> Self {
-        let maze: Vec<_> = input.lines().map(|line| line.chars().collect()).collect();
+        let parsed = GridParser::allowing("|-LJ7F.S")
+            .with_marker('S')
+            .parse(input)
+            .unwrap_or_else(|e| panic!("Invalid maze: {e}"));
+        let starting_position = parsed.marker('S');
+        let maze = parsed.grid;
         let loop_markings: Vec<_> = maze
             .iter()
             .map(|l: &Vec<char>| l.iter().map(|_c| false).collect())
             .collect();
-        let starting_position = get_starting_position(&maze);
         Self {
             maze,
             starting_position,
@@ -38,66 +34,83 @@ impl Maze {
         }
     }
 
-    fn mark_loop(&mut self, position: Position, from: Direction) -> Option<u32> {
+    // Directions a tile's pipe connects to; 'S' may connect anywhere.
+    fn connections(tile: char) -> &'static [Direction] {
         use Direction::*;
 
-        if !position.is_in_bounds(&self.maze) {
-            return None;
+        match tile {
+            '|' => &[North, South],
+            '-' => &[East, West],
+            'L' => &[North, East],
+            'J' => &[North, West],
+            '7' => &[South, West],
+            'F' => &[South, East],
+            'S' => &[North, South, East, West],
+            _ => &[],
         }
-        return match self.maze[position.0 as usize][position.1 as usize] {
-            'S' => Some(0),
-            '.' => None,
-            '|' => match from {
-                North => self.mark_loop(position.step(South)?, North),
-                South => self.mark_loop(position.step(North)?, South),
-                _ => None,
-            },
-            '-' => match from {
-                West => self.mark_loop(position.step(East)?, West),
-                East => self.mark_loop(position.step(West)?, East),
-                _ => None,
-            },
-            'L' => match from {
-                North => self.mark_loop(position.step(East)?, West),
-                East => self.mark_loop(position.step(North)?, South),
-                _ => None,
-            },
-            'J' => match from {
-                North => self.mark_loop(position.step(West)?, East),
-                West => self.mark_loop(position.step(North)?, South),
-                _ => None,
-            },
-            '7' => match from {
-                South => self.mark_loop(position.step(West)?, East),
-                West => self.mark_loop(position.step(South)?, North),
-                _ => None,
-            },
-            'F' => match from {
-                South => self.mark_loop(position.step(East)?, West),
-                East => self.mark_loop(position.step(South)?, North),
-                _ => None,
-            },
-            _ => None,
+    }
+
+    // Whether leaving 'S' towards `direction` follows pipes all the way back round to 'S'.
+    fn leads_back_to_start(&self, direction: Direction) -> bool {
+        let mut position = self.starting_position;
+        let mut heading = direction;
+        loop {
+            let Some(next) = position.step_within_grid(heading, &self.maze) else {
+                return false;
+            };
+            let connections = Self::connections(self.maze[next.0][next.1]);
+            if !connections.contains(&heading.opposite()) {
+                return false;
+            }
+            if next == self.starting_position {
+                return true;
+            }
+            heading = *connections
+                .iter()
+                .find(|&&d| d != heading.opposite())
+                .unwrap();
+            position = next;
         }
-        .map(|v: u32| {
-            self.loop_markings[position.0 as usize][position.1 as usize] = true;
-            v + 1
-        });
     }
 
-    fn get_loop_size_and_mark_loop(&mut self) -> u32 {
-        use Direction::*;
+    // Marks every tile on the loop through 'S' and returns the distance to its farthest tile.
+    fn mark_loop_and_get_farthest_distance(&mut self) -> usize {
+        // Pipes next to 'S' may point into it without being on the loop, so only follow the
+        // two that do come back round.
+        let start_connections: Vec<_> = Self::connections('S')
+            .iter()
+            .copied()
+            .filter(|&direction| self.leads_back_to_start(direction))
+            .collect();
+        if start_connections.len() != 2 {
+            panic!("No loop detected!");
+        }
 
-        for direction in [North, South, East, West] {
-            if let Some(distance) = self
-                .starting_position
-                .step(direction)
-                .and_then(|next_position| self.mark_loop(next_position, direction.opposite()))
-            {
-                return distance;
-            }
+        let maze = &self.maze;
+        let starting_position = self.starting_position;
+        let result = Bfs::with_store([starting_position], PositionMap::for_grid(maze)).run(
+            |position: &Position| {
+                let connections = if *position == starting_position {
+                    start_connections.as_slice()
+                } else {
+                    Self::connections(maze[position.0][position.1])
+                };
+                connections
+                    .iter()
+                    .filter_map(|&direction| {
+                        let neighbor = position.step_within_grid(direction, maze)?;
+                        Self::connections(maze[neighbor.0][neighbor.1])
+                            .contains(&direction.opposite())
+                            .then_some(neighbor)
+                    })
+                    .collect::<Vec<_>>()
+            },
+        );
+
+        for (position, _) in result.distances.iter() {
+            self.loop_markings[position.0][position.1] = true;
         }
-        panic!("No loop detected!");
+        return result.max_distance().unwrap();
     }
 
     fn count_empty_spaces_within_line<I>(&self, i: usize, indices: I) -> u32
@@ -149,12 +162,12 @@ impl Maze {
 impl Day for Day10 {
     fn part1(&self, input: &str) -> Result<Box<dyn Display>, &str> {
         let mut maze = Maze::parse_from_input(input);
-        return Ok(Box::new(maze.get_loop_size_and_mark_loop() / 2));
+        return Ok(Box::new(maze.mark_loop_and_get_farthest_distance()));
     }
 
     fn part2(&self, input: &str) -> Result<Box<dyn Display>, &str> {
         let mut maze = Maze::parse_from_input(input);
-        maze.get_loop_size_and_mark_loop();
+        maze.mark_loop_and_get_farthest_distance();
 
         let mut num_spaces = 0;
         for (i, line) in maze.maze.iter().enumerate() {
@@ -178,4 +191,13 @@ impl Day for Day10 {
 mod tests {
     use super::*;
     use googletest::prelude::*;
+
+    #[googletest::test]
+    fn ignores_pipes_into_start_that_are_off_the_loop() {
+        let input = "------S-7
+......|.|
+......L-J";
+        expect_that!(Day10 {}.part1(input).unwrap().to_string(), eq("4"));
+        expect_that!(Day10 {}.part2(input).unwrap().to_string(), eq("1"));
+    }
 }
@@ -1,97 +1,77 @@
-use crate::day::Day;
+use crate::common::point::{Point, Position};
+use crate::day::{Day, Params};
 use std::fmt::Display;
 
 pub struct Day11 {}
 
-impl Day for Day11 {
-    fn part1(&self, input: &str) -> Result<Box<dyn Display>, &str> {
-        let galaxy: Vec<Vec<char>> = input.lines().map(|l| l.chars().collect()).collect();
-        let mut star_positions: Vec<(usize, usize)> = vec![];
-        for (i, line) in galaxy.iter().enumerate() {
-            for (j, &c) in line.iter().enumerate() {
-                if c == '#' {
-                    star_positions.push((i, j));
-                }
+// Sum of distances between every pair of galaxies, after each empty row and column has grown
+// to `expansion_factor` rows or columns.
+fn sum_of_expanded_distances(input: &str, expansion_factor: i64) -> i64 {
+    let galaxy: Vec<Vec<char>> = input.lines().map(|l| l.chars().collect()).collect();
+    let mut star_positions: Vec<Position> = vec![];
+    for (i, line) in galaxy.iter().enumerate() {
+        for (j, &c) in line.iter().enumerate() {
+            if c == '#' {
+                star_positions.push(Point(i, j));
             }
         }
+    }
 
-        let double_width_rows: Vec<usize> = (0..galaxy.len())
-            .filter(|i| galaxy[*i].iter().all(|c| *c == '.'))
-            .collect();
-        let double_width_columns: Vec<usize> = (0..galaxy[0].len())
-            .filter(|j| galaxy.iter().all(|l| l[*j] == '.'))
-            .collect();
+    let double_width_rows: Vec<usize> = (0..galaxy.len())
+        .filter(|i| galaxy[*i].iter().all(|c| *c == '.'))
+        .collect();
+    let double_width_columns: Vec<usize> = (0..galaxy[0].len())
+        .filter(|j| galaxy.iter().all(|l| l[*j] == '.'))
+        .collect();
 
-        let mut sum_of_distances = 0;
-        for i in 0..star_positions.len() {
-            for j in (i + 1)..star_positions.len() {
-                sum_of_distances +=
-                    num::abs(star_positions[j].0 as i32 - star_positions[i].0 as i32)
-                        + num::abs(star_positions[j].1 as i32 - star_positions[i].1 as i32);
+    let mut sum_of_distances = 0;
+    for i in 0..star_positions.len() {
+        for j in (i + 1)..star_positions.len() {
+            sum_of_distances += star_positions[j].manhattan_distance(&star_positions[i]) as i64;
 
-                for &col in &double_width_rows {
-                    if (star_positions[i].0 < col && col < star_positions[j].0)
-                        || (star_positions[j].0 < col && col < star_positions[i].0)
-                    {
-                        sum_of_distances += 1;
-                    }
+            for &col in &double_width_rows {
+                if (star_positions[i].0 < col && col < star_positions[j].0)
+                    || (star_positions[j].0 < col && col < star_positions[i].0)
+                {
+                    sum_of_distances += expansion_factor - 1;
                 }
-                for &col in &double_width_columns {
-                    if (star_positions[i].1 < col && col < star_positions[j].1)
-                        || (star_positions[j].1 < col && col < star_positions[i].1)
-                    {
-                        sum_of_distances += 1;
-                    }
+            }
+            for &col in &double_width_columns {
+                if (star_positions[i].1 < col && col < star_positions[j].1)
+                    || (star_positions[j].1 < col && col < star_positions[i].1)
+                {
+                    sum_of_distances += expansion_factor - 1;
                 }
             }
         }
-
-        return Ok(Box::new(sum_of_distances));
     }
 
-    fn part2(&self, input: &str) -> Result<Box<dyn Display>, &str> {
-        let galaxy: Vec<Vec<char>> = input.lines().map(|l| l.chars().collect()).collect();
-        let mut star_positions: Vec<(usize, usize)> = vec![];
-        for (i, line) in galaxy.iter().enumerate() {
-            for (j, &c) in line.iter().enumerate() {
-                if c == '#' {
-                    star_positions.push((i, j));
-                }
-            }
-        }
+    return sum_of_distances;
+}
 
-        let double_width_rows: Vec<usize> = (0..galaxy.len())
-            .filter(|i| galaxy[*i].iter().all(|c| *c == '.'))
-            .collect();
-        let double_width_columns: Vec<usize> = (0..galaxy[0].len())
-            .filter(|j| galaxy.iter().all(|l| l[*j] == '.'))
-            .collect();
+impl Day for Day11 {
+    fn default_params(&self) -> Params {
+        return Params::new()
+            .with("part1_expansion_factor", 2)
+            .with("part2_expansion_factor", 1000000);
+    }
 
-        let mut sum_of_distances = 0;
-        for i in 0..star_positions.len() {
-            for j in (i + 1)..star_positions.len() {
-                sum_of_distances +=
-                    num::abs(star_positions[j].0 as i64 - star_positions[i].0 as i64)
-                        + num::abs(star_positions[j].1 as i64 - star_positions[i].1 as i64);
+    fn part1(&self, input: &str) -> Result<Box<dyn Display>, &str> {
+        return self.part1_with_params(input, &self.default_params());
+    }
 
-                for &col in &double_width_rows {
-                    if (star_positions[i].0 < col && col < star_positions[j].0)
-                        || (star_positions[j].0 < col && col < star_positions[i].0)
-                    {
-                        sum_of_distances += 999999;
-                    }
-                }
-                for &col in &double_width_columns {
-                    if (star_positions[i].1 < col && col < star_positions[j].1)
-                        || (star_positions[j].1 < col && col < star_positions[i].1)
-                    {
-                        sum_of_distances += 999999;
-                    }
-                }
-            }
-        }
+    fn part1_with_params(&self, input: &str, params: &Params) -> Result<Box<dyn Display>, &str> {
+        let expansion_factor = params.get_or_panic("part1_expansion_factor");
+        return Ok(Box::new(sum_of_expanded_distances(input, expansion_factor)));
+    }
 
-        return Ok(Box::new(sum_of_distances));
+    fn part2(&self, input: &str) -> Result<Box<dyn Display>, &str> {
+        return self.part2_with_params(input, &self.default_params());
+    }
+
+    fn part2_with_params(&self, input: &str, params: &Params) -> Result<Box<dyn Display>, &str> {
+        let expansion_factor = params.get_or_panic("part2_expansion_factor");
+        return Ok(Box::new(sum_of_expanded_distances(input, expansion_factor)));
     }
 }
 
@@ -99,4 +79,32 @@ impl Day for Day11 {
 mod tests {
     use super::*;
     use googletest::prelude::*;
+
+    const EXAMPLE: &str = "...#......
+.......#..
+#.........
+..........
+......#...
+.#........
+.........#
+..........
+.......#..
+#...#.....";
+
+    #[googletest::test]
+    fn sums_distances_with_configured_expansion() {
+        expect_that!(Day11 {}.part1(EXAMPLE).unwrap().to_string(), eq("374"));
+        for (expansion_factor, sum) in [(10, "1030"), (100, "8410")] {
+            let params = Day11 {}
+                .default_params()
+                .with("part2_expansion_factor", expansion_factor);
+            expect_that!(
+                Day11 {}
+                    .part2_with_params(EXAMPLE, &params)
+                    .unwrap()
+                    .to_string(),
+                eq(sum)
+            );
+        }
+    }
 }
@@ -1,8 +1,6 @@
-use crate::day::Day;
-use std::{
-    collections::{hash_map::Entry, HashMap},
-    fmt::Display,
-};
+use crate::day::{Day, Params};
+use num::{BigUint, One, Zero};
+use std::fmt::Display;
 
 pub struct Day12 {}
 
@@ -12,210 +10,77 @@ struct HotSpring {
 }
 
 impl HotSpring {
-    fn insert_into_entry(
-        memoization: &mut HashMap<usize, HashMap<Vec<usize>, HashMap<bool, i64>>>,
-        starting_idx: usize,
-        numbers: &Vec<usize>,
-        require_spring: bool,
-        result: i64,
-    ) -> i64 {
-        *memoization
-            .entry(starting_idx)
-            .or_insert(HashMap::new())
-            .entry(numbers.clone())
-            .or_insert(HashMap::new())
-            .entry(require_spring)
-            .or_insert(result)
-    }
-
-    fn get_num_arrangements_helper(
-        &self,
-        memoization: &mut HashMap<usize, HashMap<Vec<usize>, HashMap<bool, i64>>>,
-        starting_idx: usize,
-        numbers: &Vec<usize>,
-        require_spring: bool,
-    ) -> i64 {
-        if let Entry::Occupied(occupied) = memoization
-            .entry(starting_idx)
-            .or_insert(HashMap::new())
-            .entry(numbers.clone())
-            .or_insert(HashMap::new())
-            .entry(require_spring)
-        {
-            return *occupied.get();
-        }
-
-        if starting_idx == self.chars.len() {
-            if numbers.is_empty() || numbers.len() == 1 && numbers[0] == 0 {
-                return Self::insert_into_entry(
-                    memoization,
-                    starting_idx,
-                    numbers,
-                    require_spring,
-                    1,
-                );
-            } else {
-                return Self::insert_into_entry(
-                    memoization,
-                    starting_idx,
-                    numbers,
-                    require_spring,
-                    0,
-                );
-            }
+    // Compiles the group sizes into the pattern every arrangement must match: a '#' state for
+    // each damaged spring, with '.' states before, between and after the groups. A '.' state
+    // may repeat; a '#' state must be followed by the next state.
+    fn compile_groups(&self) -> Vec<char> {
+        let mut states = vec!['.'];
+        for &size in self.numbers.iter().filter(|&&size| size > 0) {
+            states.extend(std::iter::repeat_n('#', size));
+            states.push('.');
         }
+        states
+    }
 
-        match self.chars[starting_idx] {
-            '.' => {
-                if require_spring {
-                    return 0;
-                } else if !numbers.is_empty() && numbers[0] == 0 {
-                    let new_numbers = numbers[1..].to_vec();
-                    let result = self.get_num_arrangements_helper(
-                        memoization,
-                        starting_idx + 1,
-                        &new_numbers,
-                        /*require_spring=*/ false,
-                    );
-                    return Self::insert_into_entry(
-                        memoization,
-                        starting_idx,
-                        numbers,
-                        require_spring,
-                        result,
-                    );
-                } else {
-                    let result = self.get_num_arrangements_helper(
-                        memoization,
-                        starting_idx + 1,
-                        numbers,
-                        /*require_spring=*/ false,
-                    );
-                    return Self::insert_into_entry(
-                        memoization,
-                        starting_idx,
-                        numbers,
-                        require_spring,
-                        result,
-                    );
-                }
-            }
-            '#' => {
-                if numbers.is_empty() || numbers[0] == 0 {
-                    return Self::insert_into_entry(
-                        memoization,
-                        starting_idx,
-                        numbers,
-                        require_spring,
-                        0,
-                    );
-                } else {
-                    let mut new_numbers = numbers.clone();
-                    new_numbers[0] -= 1;
+    // Runs the compiled pattern as an NFA over the characters, counting the ways to reach each
+    // state rather than tracking them individually. Linear in chars * states.
+    fn get_num_arrangements(&self) -> BigUint {
+        let states = self.compile_groups();
+        let mut counts = vec![BigUint::zero(); states.len()];
+        counts[0] = BigUint::one();
 
-                    let result = self.get_num_arrangements_helper(
-                        memoization,
-                        starting_idx + 1,
-                        &new_numbers,
-                        /*require_spring=*/ new_numbers[0] != 0,
-                    );
-                    return Self::insert_into_entry(
-                        memoization,
-                        starting_idx,
-                        numbers,
-                        require_spring,
-                        result,
-                    );
-                }
+        for &c in &self.chars {
+            if !matches!(c, '.' | '#' | '?') {
+                panic!("Unexpected input character.");
             }
-            '?' => {
-                if require_spring && (numbers.is_empty() || numbers[0] == 0) {
-                    return Self::insert_into_entry(
-                        memoization,
-                        starting_idx,
-                        numbers,
-                        require_spring,
-                        0,
-                    );
+            let mut next_counts = vec![BigUint::zero(); states.len()];
+            for (i, count) in counts
+                .iter()
+                .enumerate()
+                .filter(|(_, count)| !count.is_zero())
+            {
+                let next_state = states.get(i + 1);
+                if matches!(c, '.' | '?') {
+                    if states[i] == '.' {
+                        next_counts[i] += count;
+                    }
+                    if states[i] == '#' && next_state == Some(&'.') {
+                        next_counts[i + 1] += count;
+                    }
                 }
-                if numbers.is_empty() {
-                    // If there are no more numbers, this is '.', so there's only one option.
-                    let result = self.get_num_arrangements_helper(
-                        memoization,
-                        starting_idx + 1,
-                        numbers,
-                        /*require_spring=*/ false,
-                    );
-                    return Self::insert_into_entry(
-                        memoization,
-                        starting_idx,
-                        numbers,
-                        require_spring,
-                        result,
-                    );
-                } else if numbers[0] == 0 {
-                    // If this needs to be the end of a sequence, it has to be '.', so there's only one option.
-                    let new_numbers = numbers[1..].to_vec();
-                    let result = self.get_num_arrangements_helper(
-                        memoization,
-                        starting_idx + 1,
-                        &new_numbers,
-                        /*require_spring=*/ false,
-                    );
-                    return Self::insert_into_entry(
-                        memoization,
-                        starting_idx,
-                        numbers,
-                        require_spring,
-                        result,
-                    );
-                } else {
-                    // Otherwise, it can be '.' or '#'.
-                    let mut new_numbers: Vec<usize> = numbers.clone();
-                    new_numbers[0] -= 1;
-                    let options_for_space = if require_spring {
-                        0
-                    } else {
-                        self.get_num_arrangements_helper(
-                            memoization,
-                            starting_idx + 1,
-                            numbers,
-                            /*require_spring=*/ false,
-                        )
-                    };
-                    let options_for_spring = self.get_num_arrangements_helper(
-                        memoization,
-                        starting_idx + 1,
-                        &new_numbers,
-                        /*require_spring=*/ new_numbers[0] != 0,
-                    );
-                    return Self::insert_into_entry(
-                        memoization,
-                        starting_idx,
-                        numbers,
-                        require_spring,
-                        options_for_space + options_for_spring,
-                    );
+                if matches!(c, '#' | '?') && next_state == Some(&'#') {
+                    next_counts[i + 1] += count;
                 }
             }
-            _ => panic!("Unexpected input character."),
+            counts = next_counts;
         }
-    }
 
-    fn get_num_arrangements(&self) -> i64 {
-        let mut memoization: HashMap<usize, HashMap<Vec<usize>, HashMap<bool, i64>>> =
-            HashMap::new();
+        // Either in the trailing '.' state, or just finished the last group.
+        let last = states.len() - 1;
+        let mut num_arrangements = counts[last].clone();
+        if last > 0 {
+            num_arrangements += &counts[last - 1];
+        }
+        num_arrangements
+    }
 
-        self.get_num_arrangements_helper(
-            &mut memoization,
-            0,
-            &self.numbers,
-            /*require_spring=*/ false,
-        )
+    // Repeats the springs `factor` times separated by '?', and the groups `factor` times.
+    fn unfold(&self, factor: usize) -> HotSpring {
+        let mut chars = vec![];
+        for copy in 0..factor {
+            if copy > 0 {
+                chars.push('?');
+            }
+            chars.extend(&self.chars);
+        }
+        HotSpring {
+            chars,
+            numbers: self.numbers.repeat(factor),
+        }
     }
 }
 
-fn parse_spring_part1(line: &str) -> HotSpring {
+fn parse_spring(line: &str) -> HotSpring {
     let mut items = line.split_ascii_whitespace();
     HotSpring {
         chars: items.next().unwrap().chars().collect(),
@@ -228,24 +93,14 @@ fn parse_spring_part1(line: &str) -> HotSpring {
     }
 }
 
-fn parse_spring_part2(line: &str) -> HotSpring {
-    let mut items = line.split_ascii_whitespace();
-    let chars = items.next().unwrap();
-    let chars = (0..5).map(|_| chars).collect::<Vec<&str>>().join("?");
-
-    let numbers = items.next().unwrap();
-    let numbers = (0..5).map(|_| numbers).collect::<Vec<&str>>().join(",");
-
-    HotSpring {
-        chars: chars.chars().collect(),
-        numbers: numbers.split(',').map(|n| n.parse().unwrap()).collect(),
+impl Day for Day12 {
+    fn default_params(&self) -> Params {
+        return Params::new().with("unfold_factor", 5);
     }
-}
 
-impl Day for Day12 {
     fn part1(&self, input: &str) -> Result<Box<dyn Display>, &str> {
-        let hot_springs: Vec<_> = input.lines().map(|l| parse_spring_part1(l)).collect();
-        let num_arrangements: i64 = hot_springs
+        let hot_springs: Vec<_> = input.lines().map(parse_spring).collect();
+        let num_arrangements: BigUint = hot_springs
             .into_iter()
             .map(|spring| spring.get_num_arrangements())
             .sum();
@@ -253,8 +108,16 @@ impl Day for Day12 {
     }
 
     fn part2(&self, input: &str) -> Result<Box<dyn Display>, &str> {
-        let hot_springs: Vec<_> = input.lines().map(|l| parse_spring_part2(l)).collect();
-        let num_arrangements: i64 = hot_springs
+        return self.part2_with_params(input, &self.default_params());
+    }
+
+    fn part2_with_params(&self, input: &str, params: &Params) -> Result<Box<dyn Display>, &str> {
+        let unfold_factor: usize = params.get_or_panic("unfold_factor");
+        let hot_springs: Vec<_> = input
+            .lines()
+            .map(|l| parse_spring(l).unfold(unfold_factor))
+            .collect();
+        let num_arrangements: BigUint = hot_springs
             .into_iter()
             .map(|spring| spring.get_num_arrangements())
             .sum();
@@ -265,11 +128,81 @@ impl Day for Day12 {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::common::memo::Memo;
     use googletest::prelude::*;
+    use itertools::Itertools;
+    use num::ToPrimitive;
+
+    // Memo key: (starting_idx, group, remaining, require_spring), as in `count_arrangements`.
+    type ArrangementMemo = Memo<(usize, usize, usize, bool), u64>;
 
-    fn get_num_arrangements_helper(pattern: &str, numbers: Vec<usize>) -> i64 {
+    // Reference counter that recurses per character: arrangements of chars[starting_idx..] into
+    // the groups numbers[group..], where only `remaining` springs are left to place in the first
+    // of those. `require_spring` is set in the middle of a group, when the next character must
+    // continue it.
+    fn count_arrangements(
+        spring: &HotSpring,
+        memo: &mut ArrangementMemo,
+        starting_idx: usize,
+        group: usize,
+        remaining: usize,
+        require_spring: bool,
+    ) -> u64 {
+        let key = (starting_idx, group, remaining, require_spring);
+        memo.get_or_compute(key, |memo| {
+            let no_groups_left = group == spring.numbers.len();
+            if starting_idx == spring.chars.len() {
+                let finished =
+                    no_groups_left || group + 1 == spring.numbers.len() && remaining == 0;
+                return if finished { 1 } else { 0 };
+            }
+
+            // Only the branches the character allows are explored.
+            let options_for_space = |memo: &mut ArrangementMemo| {
+                if require_spring {
+                    0
+                } else if !no_groups_left && remaining == 0 {
+                    // This group's been closed off with a '.', so move on to the next.
+                    let next_remaining = spring.numbers.get(group + 1).copied().unwrap_or(0);
+                    let next = starting_idx + 1;
+                    count_arrangements(spring, memo, next, group + 1, next_remaining, false)
+                } else {
+                    count_arrangements(spring, memo, starting_idx + 1, group, remaining, false)
+                }
+            };
+            let options_for_spring = |memo: &mut ArrangementMemo| {
+                if no_groups_left || remaining == 0 {
+                    0
+                } else {
+                    let next = starting_idx + 1;
+                    count_arrangements(spring, memo, next, group, remaining - 1, remaining > 1)
+                }
+            };
+
+            match spring.chars[starting_idx] {
+                '.' => options_for_space(memo),
+                '#' => options_for_spring(memo),
+                '?' => options_for_space(memo) + options_for_spring(memo),
+                _ => panic!("Unexpected input character."),
+            }
+        })
+    }
+
+    fn count_arrangements_with_memo(pattern: &str, numbers: Vec<usize>) -> u64 {
+        let spring = HotSpring {
+            chars: pattern.chars().collect(),
+            numbers,
+        };
+        let first_remaining = spring.numbers.first().copied().unwrap_or(0);
+        count_arrangements(&spring, &mut Memo::new(), 0, 0, first_remaining, false)
+    }
+
+    fn get_num_arrangements_helper(pattern: &str, numbers: Vec<usize>) -> u64 {
         let chars: Vec<_> = pattern.chars().collect();
-        return HotSpring { chars, numbers }.get_num_arrangements();
+        return HotSpring { chars, numbers }
+            .get_num_arrangements()
+            .to_u64()
+            .unwrap();
     }
 
     #[googletest::test]
@@ -321,4 +254,53 @@ mod tests {
             eq(10)
         );
     }
+
+    #[googletest::test]
+    fn counts_arrangements_with_memoized_recursion() {
+        expect_that!(count_arrangements_with_memo("?", vec![0]), eq(1));
+        expect_that!(count_arrangements_with_memo("#??#", vec![2, 1]), eq(1));
+        expect_that!(
+            count_arrangements_with_memo(".??..??...?##.", vec![1, 1, 3]),
+            eq(4)
+        );
+        expect_that!(
+            count_arrangements_with_memo("?###????????", vec![3, 2, 1]),
+            eq(10)
+        );
+    }
+
+    #[googletest::test]
+    fn nfa_agrees_with_memoized_recursion() {
+        let groups = [vec![1], vec![2], vec![1, 1], vec![2, 1], vec![1, 2, 1]];
+        for pattern in (0..=6)
+            .flat_map(|len| itertools::repeat_n(['.', '#', '?'], len).multi_cartesian_product())
+            .map(|chars| chars.into_iter().collect::<String>())
+        {
+            for numbers in &groups {
+                expect_that!(
+                    get_num_arrangements_helper(&pattern, numbers.clone()),
+                    eq(count_arrangements_with_memo(&pattern, numbers.clone()))
+                );
+            }
+        }
+    }
+
+    #[googletest::test]
+    fn gets_num_arrangements_when_unfolded() {
+        let unfolded = |line: &str, factor: usize| {
+            parse_spring(line)
+                .unfold(factor)
+                .get_num_arrangements()
+                .to_string()
+        };
+        expect_that!(unfolded("???.### 1,1,3", 5), eq("1"));
+        expect_that!(unfolded(".??..??...?##. 1,1,3", 5), eq("16384"));
+        expect_that!(unfolded("?###???????? 3,2,1", 5), eq("506250"));
+        expect_that!(unfolded("?###???????? 3,2,1", 1), eq("10"));
+        // 10 * 15^(n - 1) overflows u64 once unfolded 17 times.
+        expect_that!(
+            unfolded("?###???????? 3,2,1", 20),
+            eq("221683782005310058593750")
+        );
+    }
 }
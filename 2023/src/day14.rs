@@ -1,8 +1,11 @@
+use crate::common::animation::Recorder;
+use crate::common::cycle;
 use crate::common::direction::Direction;
-use crate::common::grid;
-use crate::common::position::Position;
-use crate::day::Day;
+use crate::common::grid::{self, Color, ImageExporter};
+use crate::common::point::{Point, Position};
+use crate::day::{Day, Params};
 use std::fmt::Display;
+use std::time::Duration;
 
 pub struct Day14 {}
 
@@ -45,21 +48,21 @@ fn inplace_slide_grid_in_direction(grid: &mut grid::Grid<char>, direction: Direc
         Direction::North | Direction::West => {
             for i in 0..grid.len() {
                 for j in 0..grid[0].len() {
-                    inplace_slide_rock_in_direction(grid, Position(i, j), direction);
+                    inplace_slide_rock_in_direction(grid, Point(i, j), direction);
                 }
             }
         }
         Direction::South => {
             for i in (0..grid.len()).rev() {
                 for j in 0..grid[0].len() {
-                    inplace_slide_rock_in_direction(grid, Position(i, j), direction);
+                    inplace_slide_rock_in_direction(grid, Point(i, j), direction);
                 }
             }
         }
         Direction::East => {
             for i in 0..grid.len() {
                 for j in (0..grid[0].len()).rev() {
-                    inplace_slide_rock_in_direction(grid, Position(i, j), direction);
+                    inplace_slide_rock_in_direction(grid, Point(i, j), direction);
                 }
             }
         }
@@ -75,25 +78,40 @@ fn compute_load(grid: &grid::Grid<char>) -> i32 {
         .sum()
 }
 
+fn rock_exporter() -> ImageExporter<char> {
+    ImageExporter::new(|c: &char| match c {
+        'O' => Color::Yellow,
+        '#' => Color::Blue,
+        _ => Color::Black,
+    })
+}
+
 impl Day for Day14 {
+    fn default_params(&self) -> Params {
+        return Params::new().with("num_cycles", 1000000000);
+    }
+
     fn part1(&self, input: &str) -> Result<Box<dyn Display>, &str> {
         let mut grid = grid::parse_grid(input);
 
         inplace_slide_grid_in_direction(&mut grid, Direction::North);
+        rock_exporter().write_debug(&grid, "day14_part1.png");
 
         return Ok(Box::new(compute_load(&grid)));
     }
 
     fn part2(&self, input: &str) -> Result<Box<dyn Display>, &str> {
-        let mut grid = grid::parse_grid(input);
-        let mut past_grids = vec![grid.clone()];
+        return self.part2_with_params(input, &self.default_params());
+    }
 
-        let mut cycle_begin: usize = 0;
-        let mut cycle_end: usize = 0;
+    fn part2_with_params(&self, input: &str, params: &Params) -> Result<Box<dyn Display>, &str> {
+        let num_cycles: usize = params.get_or_panic("num_cycles");
+        let grid = grid::parse_grid(input);
+        let mut recorder = Recorder::when_debugging();
+        recorder.record(&grid);
 
-        // Original grid is at index 0, so we start at 1 for convenience
-        // Grid after X iterations = with index X, find the corresponding index in past_grids
-        for j in 1_usize..=1000000000_usize {
+        let history = cycle::find_with_history(grid, |grid| {
+            let mut grid = grid.clone();
             for direction in [
                 Direction::North,
                 Direction::West,
@@ -101,26 +119,14 @@ impl Day for Day14 {
                 Direction::East,
             ] {
                 inplace_slide_grid_in_direction(&mut grid, direction);
+                recorder.record(&grid);
             }
+            grid
+        });
 
-            if let Some((i, _)) = past_grids
-                .iter()
-                .enumerate()
-                .rev()
-                .find(|&past_grid| past_grid.1 == &grid)
-            {
-                cycle_begin = i;
-                cycle_end = j;
-                break;
-            }
+        recorder.write_debug(&rock_exporter(), "day14_part2", Duration::from_millis(50));
 
-            past_grids.push(grid.clone())
-        }
-
-        let grid_idx_at_cycle =
-            cycle_begin + (1000000000_usize - cycle_begin) % (cycle_end - cycle_begin);
-
-        return Ok(Box::new(compute_load(&past_grids[grid_idx_at_cycle])));
+        return Ok(Box::new(compute_load(history.state_after(num_cycles))));
     }
 }
 
@@ -128,4 +134,35 @@ impl Day for Day14 {
 mod tests {
     use super::*;
     use googletest::prelude::*;
+
+    const EXAMPLE: &str = "O....#....
+O.OO#....#
+.....##...
+OO.#O....O
+.O.....O#.
+O.#..O.#.#
+..O..#O..O
+.......O..
+#....###..
+#OO..#....";
+
+    #[googletest::test]
+    fn computes_load_after_many_spin_cycles() {
+        expect_that!(Day14 {}.part2(EXAMPLE).unwrap().to_string(), eq("64"));
+    }
+
+    #[googletest::test]
+    fn computes_load_after_few_spin_cycles() {
+        // The example's load after 1, 2 and 3 cycles; it starts repeating after the third.
+        for (num_cycles, load) in [(1, "87"), (2, "69"), (3, "69")] {
+            let params = Day14 {}.default_params().with("num_cycles", num_cycles);
+            expect_that!(
+                Day14 {}
+                    .part2_with_params(EXAMPLE, &params)
+                    .unwrap()
+                    .to_string(),
+                eq(load)
+            );
+        }
+    }
 }
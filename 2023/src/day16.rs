@@ -1,10 +1,8 @@
-use itertools::Itertools;
-
 use crate::common::direction::Direction;
 use crate::common::grid::{self, Grid};
-use crate::common::position::Position;
+use crate::common::point::{Point, Position};
+use crate::common::position_set::PositionSet;
 use crate::day::Day;
-use std::collections::HashSet;
 use std::fmt::Display;
 
 pub struct Day16 {}
@@ -21,22 +19,22 @@ fn step(
 }
 
 fn count_energized_tiles(grid: &Grid<char>, starting: (Position, Direction)) -> usize {
-    let mut visited: HashSet<_> = HashSet::<(Position, Direction)>::new();
+    let mut visited = PositionSet::<(Position, Direction)>::for_grid(grid);
+    let mut energized = PositionSet::<Position>::for_grid(grid);
     let mut next_positions = vec![starting];
 
     while let Some((position, heading)) = next_positions.pop() {
-        if visited.contains(&(position, heading)) {
+        if !visited.insert((position, heading)) {
             continue;
         }
-
-        visited.insert((position.clone(), heading));
+        energized.insert(position);
 
         match grid[position.0][position.1] {
             '.' => step(&mut next_positions, &position, heading, &grid),
-            '/' | '\\' => step(
+            mirror @ ('/' | '\\') => step(
                 &mut next_positions,
                 &position,
-                heading.reflect(grid[position.0][position.1]),
+                heading.reflect(mirror).unwrap(),
                 &grid,
             ),
             '|' => match heading {
@@ -61,7 +59,7 @@ fn count_energized_tiles(grid: &Grid<char>, starting: (Position, Direction)) ->
         }
     }
 
-    return visited.iter().map(|pair| pair.0).unique().count();
+    return energized.len();
 }
 
 impl Day for Day16 {
@@ -69,7 +67,7 @@ impl Day for Day16 {
         let grid = grid::parse_grid(input);
         return Ok(Box::new(count_energized_tiles(
             &grid,
-            (Position(0, 0), Direction::East),
+            (Point(0, 0), Direction::East),
         )));
     }
 
@@ -80,10 +78,10 @@ impl Day for Day16 {
         let num_columns = grid[0].len();
 
         let max_energized_tiles = (0..num_rows)
-            .map(|i| (Position(i, 0), Direction::East))
-            .chain((0..num_rows).map(|i| (Position(i, num_columns - 1), Direction::West)))
-            .chain((0..num_columns).map(|j| (Position(0, j), Direction::South)))
-            .chain((0..num_columns).map(|j| (Position(num_rows - 1, j), Direction::North)))
+            .map(|i| (Point(i, 0), Direction::East))
+            .chain((0..num_rows).map(|i| (Point(i, num_columns - 1), Direction::West)))
+            .chain((0..num_columns).map(|j| (Point(0, j), Direction::South)))
+            .chain((0..num_columns).map(|j| (Point(num_rows - 1, j), Direction::North)))
             .map(|starting| count_energized_tiles(&grid, starting))
             .max()
             .unwrap();
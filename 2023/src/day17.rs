@@ -1,215 +1,150 @@
-use priority_queue::PriorityQueue;
-
-use crate::common::direction::Direction;
-use crate::common::grid::{parse_transform_grid, Grid};
-use crate::common::position::Position;
+use crate::common::direction::{Direction, Turn};
+use crate::common::grid::{Grid, GridParser};
+use crate::common::point::{Point, Position};
+use crate::common::position_set::{PositionKey, PositionMap};
+use crate::common::search;
 use crate::day::Day;
-use std::cmp::Reverse;
-use std::collections::HashMap;
 use std::fmt::Display;
 
 pub struct Day17 {}
 
-#[derive(PartialEq, Eq, Hash, Debug)]
-struct GridState {
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+struct Crucible {
     position: Position,
     direction: Direction,
-    minimum_steps_before_turning: u32,
-    remaining_steps_in_direction: u32,
-    total_heat_loss: u32,
+    steps_in_direction: u32,
 }
 
-fn dijkstra_grid_for_minimum_heat_loss_part1(grid: &Grid<u32>) -> u32 {
-    let mut visited_positions: HashMap<Position, u32> = HashMap::new();
-    let mut next_positions: PriorityQueue<GridState, Reverse<u32>> = PriorityQueue::new();
-    next_positions.push(
-        GridState {
-            position: Position(0, 0),
-            direction: Direction::East,
-            minimum_steps_before_turning: 0,
-            remaining_steps_in_direction: 3,
-            total_heat_loss: 0,
-        },
-        Reverse(0),
-    );
-    next_positions.push(
-        GridState {
-            position: Position(0, 0),
-            direction: Direction::South,
-            minimum_steps_before_turning: 0,
-            remaining_steps_in_direction: 3,
-            total_heat_loss: 0,
-        },
-        Reverse(0),
-    );
-
-    while let Some((grid_state, _)) = next_positions.pop() {
-        // How to more effectively shrink the search space?
-        if visited_positions.contains_key(&grid_state.position)
-            && grid_state.total_heat_loss - visited_positions[&grid_state.position] >= 10
-        {
-            continue;
-        }
+// Longest straight run any crucible can make, which bounds how many states share a position.
+const MAX_STEPS_IN_DIRECTION: u32 = 10;
+const STEP_LAYERS: usize = MAX_STEPS_IN_DIRECTION as usize + 1;
 
-        if grid_state.position.0 == grid.len() - 1 && grid_state.position.1 == grid[0].len() - 1 {
-            return grid_state.total_heat_loss;
-        }
+impl PositionKey for Crucible {
+    const LAYERS: usize = <(Position, Direction)>::LAYERS * STEP_LAYERS;
 
-        for next_direction in [
-            Direction::North,
-            Direction::South,
-            Direction::East,
-            Direction::West,
-        ] {
-            if next_direction == grid_state.direction.opposite() {
-                continue;
-            } else if grid_state.direction == next_direction
-                && grid_state.remaining_steps_in_direction == 0
-            {
-                continue;
-            } else if let Some(next_position) =
-                grid_state.position.step_within_grid(next_direction, grid)
-            {
-                let next_heat_loss =
-                    grid_state.total_heat_loss + grid[next_position.0][next_position.1];
-                next_positions.push(
-                    GridState {
-                        position: next_position,
-                        direction: next_direction,
-                        minimum_steps_before_turning: 0,
-                        remaining_steps_in_direction: if grid_state.direction == next_direction {
-                            grid_state.remaining_steps_in_direction - 1
-                        } else {
-                            2
-                        },
-                        total_heat_loss: next_heat_loss,
-                    },
-                    Reverse(next_heat_loss),
-                );
-            }
-        }
+    fn position(&self) -> Position {
+        self.position
+    }
 
-        visited_positions
-            .entry(grid_state.position)
-            .or_insert(grid_state.total_heat_loss);
+    fn layer(&self) -> usize {
+        (self.position, self.direction).layer() * STEP_LAYERS + self.steps_in_direction as usize
     }
 
-    panic!("Didn't reach the end somehow");
+    fn from_parts(position: Position, layer: usize) -> Self {
+        let (_, direction) = <(Position, Direction)>::from_parts(position, layer / STEP_LAYERS);
+        Self {
+            position,
+            direction,
+            steps_in_direction: (layer % STEP_LAYERS) as u32,
+        }
+    }
 }
 
-fn dijkstra_grid_for_minimum_heat_loss_part2(grid: &Grid<u32>) -> u32 {
-    let mut visited_positions: HashMap<Position, u32> = HashMap::new();
-    let mut next_positions: PriorityQueue<GridState, Reverse<u32>> = PriorityQueue::new();
-    next_positions.push(
-        GridState {
-            position: Position(0, 0),
-            direction: Direction::East,
-            minimum_steps_before_turning: 4,
-            remaining_steps_in_direction: 10,
-            total_heat_loss: 0,
-        },
-        Reverse(0),
-    );
-    next_positions.push(
-        GridState {
-            position: Position(0, 0),
-            direction: Direction::South,
-            minimum_steps_before_turning: 4,
-            remaining_steps_in_direction: 10,
-            total_heat_loss: 0,
+// The crucible must move at least `min_steps_before_turning` blocks in a direction before it
+// can turn or stop, and at most `max_steps_in_direction`.
+fn minimum_heat_loss(
+    grid: &Grid<u32>,
+    min_steps_before_turning: u32,
+    max_steps_in_direction: u32,
+) -> u32 {
+    assert!(max_steps_in_direction <= MAX_STEPS_IN_DIRECTION);
+    let goal = Point(grid.len() - 1, grid[0].len() - 1);
+    let starts = [Direction::East, Direction::South].map(|direction| Crucible {
+        position: Point(0, 0),
+        direction,
+        steps_in_direction: 0,
+    });
+
+    let successors = |crucible: &Crucible| {
+        [Turn::Straight, Turn::Left, Turn::Right]
+            .into_iter()
+            .filter(|&turn| match turn {
+                Turn::Straight => crucible.steps_in_direction < max_steps_in_direction,
+                _ => crucible.steps_in_direction >= min_steps_before_turning,
+            })
+            .filter_map(|turn| {
+                let direction = crucible.direction.turn(turn);
+                let position = crucible.position.step_within_grid(direction, grid)?;
+                let steps_in_direction = if turn == Turn::Straight {
+                    crucible.steps_in_direction + 1
+                } else {
+                    1
+                };
+                let next = Crucible {
+                    position,
+                    direction,
+                    steps_in_direction,
+                };
+                Some((next, grid[position.0][position.1]))
+            })
+            .collect::<Vec<_>>()
+    };
+
+    search::a_star_with_store(
+        starts,
+        successors,
+        |crucible| crucible.position.manhattan_distance(&goal) as u32,
+        |crucible| {
+            crucible.position == goal && crucible.steps_in_direction >= min_steps_before_turning
         },
-        Reverse(0),
-    );
-
-    while let Some((grid_state, _)) = next_positions.pop() {
-        if grid_state.position.0 == grid.len() - 1 && grid_state.position.1 == grid[0].len() - 1 {
-            return grid_state.total_heat_loss;
-        }
-
-        // How to more effectively shrink the search space?
-        if visited_positions.contains_key(&grid_state.position)
-            && grid_state.total_heat_loss - visited_positions[&grid_state.position] >= 40
-        {
-            continue;
-        }
-
-        if grid_state.minimum_steps_before_turning > 0 {
-            if let Some(next_position) = grid_state
-                .position
-                .step_within_grid(grid_state.direction, grid)
-            {
-                let next_heat_loss =
-                    grid_state.total_heat_loss + grid[next_position.0][next_position.1];
-                next_positions.push(
-                    GridState {
-                        position: next_position,
-                        direction: grid_state.direction,
-                        minimum_steps_before_turning: grid_state.minimum_steps_before_turning - 1,
-                        remaining_steps_in_direction: grid_state.remaining_steps_in_direction - 1,
-                        total_heat_loss: next_heat_loss,
-                    },
-                    Reverse(next_heat_loss),
-                );
-            }
-            continue;
-        }
-
-        for next_direction in [
-            Direction::North,
-            Direction::South,
-            Direction::East,
-            Direction::West,
-        ] {
-            if next_direction == grid_state.direction.opposite() {
-                continue;
-            } else if grid_state.direction == next_direction
-                && grid_state.remaining_steps_in_direction == 0
-            {
-                continue;
-            } else if let Some(next_position) =
-                grid_state.position.step_within_grid(next_direction, grid)
-            {
-                let next_heat_loss =
-                    grid_state.total_heat_loss + grid[next_position.0][next_position.1];
-                next_positions.push(
-                    GridState {
-                        position: next_position,
-                        direction: next_direction,
-                        minimum_steps_before_turning: if grid_state.direction == next_direction {
-                            0
-                        } else {
-                            3
-                        },
-                        remaining_steps_in_direction: if grid_state.direction == next_direction {
-                            grid_state.remaining_steps_in_direction - 1
-                        } else {
-                            9
-                        },
-                        total_heat_loss: next_heat_loss,
-                    },
-                    Reverse(next_heat_loss),
-                );
-            }
-        }
-
-        visited_positions
-            .entry(grid_state.position)
-            .or_insert(grid_state.total_heat_loss);
-    }
+        PositionMap::for_grid(grid),
+    )
+    .expect("Didn't reach the end somehow")
+    .cost
+}
 
-    panic!("Didn't reach the end somehow");
+fn parse_heat_loss_grid(input: &str) -> Grid<u32> {
+    GridParser::digits()
+        .parse(input)
+        .unwrap_or_else(|e| panic!("Invalid heat loss grid: {e}"))
+        .grid
 }
 
 impl Day for Day17 {
     fn part1(&self, input: &str) -> Result<Box<dyn Display>, &str> {
-        let grid = parse_transform_grid(input, |c: char| c.to_digit(10).unwrap());
+        let grid = parse_heat_loss_grid(input);
 
-        return Ok(Box::new(dijkstra_grid_for_minimum_heat_loss_part1(&grid)));
+        return Ok(Box::new(minimum_heat_loss(&grid, 0, 3)));
     }
 
     fn part2(&self, input: &str) -> Result<Box<dyn Display>, &str> {
-        let grid = parse_transform_grid(input, |c: char| c.to_digit(10).unwrap());
+        let grid = parse_heat_loss_grid(input);
+
+        return Ok(Box::new(minimum_heat_loss(&grid, 4, 10)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use googletest::prelude::*;
+
+    const EXAMPLE: &str = "2413432311323
+3215453535623
+3255245654254
+3446585845452
+4546657867536
+1438598798454
+4457876987766
+3637877979653
+4654967986887
+4564679986453
+1224686865563
+2546548887735
+4322674655533";
+
+    #[googletest::test]
+    fn computes_minimum_heat_loss_for_example() {
+        let grid = parse_heat_loss_grid(EXAMPLE);
+        expect_that!(minimum_heat_loss(&grid, 0, 3), eq(102));
+        expect_that!(minimum_heat_loss(&grid, 4, 10), eq(94));
+    }
 
-        return Ok(Box::new(dijkstra_grid_for_minimum_heat_loss_part2(&grid)));
+    #[googletest::test]
+    fn ultra_crucible_must_move_four_blocks_before_stopping() {
+        let grid = parse_heat_loss_grid(
+            "111111111111\n999999999991\n999999999991\n999999999991\n999999999991",
+        );
+        expect_that!(minimum_heat_loss(&grid, 4, 10), eq(71));
     }
 }
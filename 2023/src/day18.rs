@@ -1,5 +1,5 @@
 use crate::{
-    common::{coordinates::Coordinates, direction::Direction},
+    common::{direction::Direction, geometry, point::Coordinates},
     day::Day,
 };
 use once_cell::sync::Lazy;
@@ -22,13 +22,7 @@ fn parse_dig_steps_part1(input: &str) -> Vec<DigStep> {
             let caps = INSTRUCTION_REGEX.captures(l).unwrap();
 
             DigStep {
-                direction: match &caps["dir"] {
-                    "U" => Direction::North,
-                    "D" => Direction::South,
-                    "L" => Direction::West,
-                    "R" => Direction::East,
-                    d => panic!("Unexpected direction {d}"),
-                },
+                direction: caps["dir"].parse().unwrap(),
                 steps: caps["steps"].parse().unwrap(),
             }
         })
@@ -44,13 +38,7 @@ fn parse_dig_steps_part2(input: &str) -> Vec<DigStep> {
             let (hex_steps, direction) = caps["hex"].split_at(5);
 
             DigStep {
-                direction: match direction {
-                    "0" => Direction::West,
-                    "1" => Direction::South,
-                    "2" => Direction::East,
-                    "3" => Direction::North,
-                    d => panic!("Unexpected direction {d}"),
-                },
+                direction: direction.parse().unwrap(),
                 steps: <i64>::from_str_radix(hex_steps, 16).unwrap(),
             }
         })
@@ -58,23 +46,15 @@ fn parse_dig_steps_part2(input: &str) -> Vec<DigStep> {
 }
 
 fn compute_area_plus_border(steps: &Vec<DigStep>) -> i64 {
-    let mut last_coordinates = Coordinates(0, 0);
-    let mut interior_size = 0;
-    let mut exterior_size = 0;
+    let mut vertices = vec![];
+    let mut last_coordinates = Coordinates::default();
 
     for step in steps.as_slice() {
-        let coordinates = last_coordinates.step(step.direction, step.steps);
-
-        if coordinates.0 != last_coordinates.0 {
-            interior_size += (coordinates.0 - last_coordinates.0) * coordinates.1;
-        }
-
-        exterior_size += step.steps;
-        last_coordinates = coordinates;
+        last_coordinates = last_coordinates + Coordinates::unit(step.direction) * step.steps;
+        vertices.push(last_coordinates);
     }
 
-    // For the padding, each border piece gets 0.5 padding, and because turns cancel out each other, the 4 corners get 1 additional size.
-    return interior_size.abs() + exterior_size / 2 + 1;
+    return geometry::enclosed_points(&vertices).expect("Lagoon is too large");
 }
 
 impl Day for Day18 {
@@ -1,3 +1,7 @@
+use crate::common::dot::DotGraph;
+use crate::common::graph::GraphKind;
+use crate::common::intern::{Interner, Symbol};
+use crate::common::interval::Interval;
 use crate::day::Day;
 use once_cell::sync::Lazy;
 use regex::Regex;
@@ -37,11 +41,17 @@ impl Condition {
     }
 }
 
+impl Display for Condition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}{}{}", self.part, self.op, self.value)
+    }
+}
+
 #[derive(Debug, PartialEq, Eq, Clone)]
 enum NextStep {
     Accept,
     Reject,
-    Goto(String),
+    Goto(Symbol),
 }
 
 #[derive(Debug)]
@@ -75,16 +85,33 @@ fn parse_condition(input: &str) -> Condition {
     }
 }
 
-fn parse_next_step(input: &str) -> NextStep {
+fn parse_next_step(input: &str, labels: &mut Interner) -> NextStep {
     match input {
         "A" => NextStep::Accept,
         "R" => NextStep::Reject,
-        n => NextStep::Goto(n.to_string()),
+        n => NextStep::Goto(labels.intern(n)),
+    }
+}
+
+struct Workflows {
+    labels: Interner,
+    // Indexed by Symbol.
+    rules: Vec<Vec<Rule>>,
+}
+
+impl Workflows {
+    fn start(&self) -> Symbol {
+        self.labels.get("in").expect("No workflow named in")
+    }
+
+    fn rules(&self, label: Symbol) -> &[Rule] {
+        &self.rules[label.index()]
     }
 }
 
-fn parse_rules(input: &str) -> HashMap<String, Vec<Rule>> {
-    let mut rules = HashMap::new();
+fn parse_rules(input: &str) -> Workflows {
+    let mut labels = Interner::new();
+    let mut rules = vec![];
 
     for line in input.lines() {
         if line.is_empty() {
@@ -92,25 +119,60 @@ fn parse_rules(input: &str) -> HashMap<String, Vec<Rule>> {
         }
         let (label, rest) = line.split_once('{').unwrap();
         let rest = rest.trim_end_matches('}');
+        let label = labels.intern(label);
 
         for rule_text in rest.split(",") {
             let rule = if let Some((condition, next_step)) = rule_text.split_once(":") {
                 Rule {
                     condition: Some(parse_condition(condition)),
-                    next_step: parse_next_step(next_step),
+                    next_step: parse_next_step(next_step, &mut labels),
                 }
             } else {
                 Rule {
                     condition: None,
-                    next_step: parse_next_step(rule_text),
+                    next_step: parse_next_step(rule_text, &mut labels),
                 }
             };
 
-            rules.entry(label.to_string()).or_insert(vec![]).push(rule);
+            rules.resize_with(labels.len(), Vec::new);
+            rules[label.index()].push(rule);
         }
     }
+    rules.resize_with(labels.len(), Vec::new);
 
-    return rules;
+    // Every workflow a rule sends parts to must be defined.
+    for (label, name) in labels.iter() {
+        if rules[label.index()].is_empty() {
+            panic!("No workflow named {name}");
+        }
+    }
+
+    return Workflows { labels, rules };
+}
+
+// Each workflow is a node, with an edge per rule labelled by its condition; rules are tried in
+// the order of their numbers.
+fn ruleset_dot_graph(ruleset: &Workflows) -> DotGraph {
+    let mut dot =
+        DotGraph::new("day19", GraphKind::Directed).with_node_defaults(&[("shape", "box")]);
+    dot.node("A", &[("label", "Accept"), ("shape", "doublecircle")]);
+    dot.node("R", &[("label", "Reject"), ("shape", "circle")]);
+
+    for (label, name) in ruleset.labels.iter() {
+        for (i, rule) in ruleset.rules(label).iter().enumerate() {
+            let destination = match &rule.next_step {
+                NextStep::Accept => "A",
+                NextStep::Reject => "R",
+                NextStep::Goto(next_label) => ruleset.labels.name(*next_label),
+            };
+            let condition = match &rule.condition {
+                Some(condition) => format!("{}: {condition}", i + 1),
+                None => format!("{}: otherwise", i + 1),
+            };
+            dot.edge(name, destination, &[("label", &condition)]);
+        }
+    }
+    return dot;
 }
 
 static PARTS_REGEX: Lazy<Regex> =
@@ -139,32 +201,23 @@ fn parse_parts(input: &str) -> Vec<Part> {
     return parts;
 }
 
-// These are pairs of [low, high), keyed by 'x', 'm', 'a', or 's'.
+// Allowed values for each of 'x', 'm', 'a', and 's'.
 #[derive(Debug, Clone)]
-struct ConstraintRange {
-    low: u64,
-    high: u64,
-}
-
-#[derive(Debug, Clone)]
-struct Constraints(HashMap<char, ConstraintRange>);
+struct Constraints(HashMap<char, Interval<u64>>);
 
 impl Constraints {
     fn world() -> Self {
         let mut ranges = HashMap::new();
-        ranges.insert('x', ConstraintRange { low: 1, high: 4001 });
-        ranges.insert('m', ConstraintRange { low: 1, high: 4001 });
-        ranges.insert('a', ConstraintRange { low: 1, high: 4001 });
-        ranges.insert('s', ConstraintRange { low: 1, high: 4001 });
+        ranges.insert('x', Interval::new(1, 4001));
+        ranges.insert('m', Interval::new(1, 4001));
+        ranges.insert('a', Interval::new(1, 4001));
+        ranges.insert('s', Interval::new(1, 4001));
 
         Self(ranges)
     }
 
     fn possible_values(&self) -> u64 {
-        self.0
-            .iter()
-            .map(|(_, r)| if r.high <= r.low { 0 } else { r.high - r.low })
-            .product()
+        self.0.values().map(|interval| interval.len()).product()
     }
 }
 
@@ -187,36 +240,31 @@ impl Partition {
 }
 
 // DFS through the ruleset, creating a tree of partitions ending in leaves.
-fn build_partition(ruleset: &HashMap<String, Vec<Rule>>) -> Partition {
+fn build_partition(ruleset: &Workflows) -> Partition {
     fn build_partition_helper(
-        ruleset: &HashMap<String, Vec<Rule>>,
+        ruleset: &Workflows,
         parent_constraint: &Constraints,
         root: &mut Partition,
-        rule_label: &str,
+        rule_label: Symbol,
     ) {
-        let rule = &ruleset[rule_label];
+        let rule = ruleset.rules(rule_label);
         let mut current_constraint = parent_constraint.clone();
 
         for r in rule {
             let mut next_constraint = current_constraint.clone();
 
             if let Some(condition) = &r.condition {
-                match condition.op {
-                    '<' => {
-                        // Low is inclusive
-                        current_constraint.0.get_mut(&condition.part).unwrap().high =
-                            condition.value;
-                        next_constraint.0.get_mut(&condition.part).unwrap().low = condition.value;
-                    }
+                let interval = current_constraint.0[&condition.part];
+                let (matching, remaining) = match condition.op {
+                    '<' => interval.split_at(condition.value),
                     '>' => {
-                        // High is exclusive
-                        current_constraint.0.get_mut(&condition.part).unwrap().low =
-                            condition.value + 1;
-                        next_constraint.0.get_mut(&condition.part).unwrap().high =
-                            condition.value + 1;
+                        let (below, above) = interval.split_at(condition.value + 1);
+                        (above, below)
                     }
                     _ => panic!("Unexpected operator."),
-                }
+                };
+                current_constraint.0.insert(condition.part, matching);
+                next_constraint.0.insert(condition.part, remaining);
             }
 
             let mut new_partition = Partition::new();
@@ -230,7 +278,7 @@ fn build_partition(ruleset: &HashMap<String, Vec<Rule>>) -> Partition {
                         ruleset,
                         &current_constraint,
                         &mut new_partition,
-                        &label,
+                        *label,
                     );
                 }
             }
@@ -241,7 +289,7 @@ fn build_partition(ruleset: &HashMap<String, Vec<Rule>>) -> Partition {
     }
 
     let mut root = Partition::new();
-    build_partition_helper(ruleset, &Constraints::world(), &mut root, "in");
+    build_partition_helper(ruleset, &Constraints::world(), &mut root, ruleset.start());
 
     return root;
 }
@@ -260,6 +308,10 @@ fn get_possible_values_in_partition(partition: &Partition) -> u64 {
 }
 
 impl Day for Day19 {
+    fn dot_graph(&self, input: &str) -> Option<DotGraph> {
+        return Some(ruleset_dot_graph(&parse_rules(input)));
+    }
+
     fn part1(&self, input: &str) -> Result<Box<dyn Display>, &str> {
         let all_rules = parse_rules(input);
         let all_parts = parse_parts(input);
@@ -267,20 +319,22 @@ impl Day for Day19 {
         let answer: u64 = all_parts
             .iter()
             .map(|part| {
-                let mut rule_label = "in";
+                let mut rule_label = all_rules.start();
 
                 loop {
-                    let rule = &all_rules[rule_label];
-                    for r in rule {
-                        if let Some(next_step) = r.execute(part) {
-                            match next_step {
-                                NextStep::Accept | NextStep::Reject => return (part, next_step),
-                                NextStep::Goto(label) => {
-                                    rule_label = &label;
-                                    break;
-                                }
-                            }
-                        }
+                    let next_step = all_rules
+                        .rules(rule_label)
+                        .iter()
+                        .find_map(|r| r.execute(part))
+                        .unwrap_or_else(|| {
+                            panic!(
+                                "No rule in workflow {} matches {part:?}",
+                                all_rules.labels.name(rule_label)
+                            )
+                        });
+                    match next_step {
+                        NextStep::Accept | NextStep::Reject => return (part, next_step),
+                        NextStep::Goto(label) => rule_label = *label,
                     }
                 }
             })
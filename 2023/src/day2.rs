@@ -1,4 +1,4 @@
-use crate::day::Day;
+use crate::day::{Day, Params};
 use once_cell::sync::Lazy;
 use regex::Regex;
 use std::fmt::Display;
@@ -6,10 +6,21 @@ use std::fmt::Display;
 pub struct Day2 {}
 
 impl Day for Day2 {
+    fn default_params(&self) -> Params {
+        return Params::new()
+            .with("red_limit", 12)
+            .with("green_limit", 13)
+            .with("blue_limit", 14);
+    }
+
     fn part1(&self, input: &str) -> Result<Box<dyn Display>, &str> {
-        let red_limit = 12;
-        let green_limit = 13;
-        let blue_limit = 14;
+        return self.part1_with_params(input, &self.default_params());
+    }
+
+    fn part1_with_params(&self, input: &str, params: &Params) -> Result<Box<dyn Display>, &str> {
+        let red_limit = params.get_or_panic("red_limit");
+        let green_limit = params.get_or_panic("green_limit");
+        let blue_limit = params.get_or_panic("blue_limit");
 
         let mut sum_of_possible_game_ids = 0;
 
@@ -1,6 +1,9 @@
+use crate::common::dot::DotGraph;
+use crate::common::graph::{Graph, NodeTable};
+use crate::common::intern::Symbol;
+use crate::common::math::{self, Congruence};
 use crate::day::Day;
 use itertools::Itertools;
-use num::Integer;
 use once_cell::sync::Lazy;
 use regex::Regex;
 use std::{
@@ -19,30 +22,31 @@ enum Pulse {
 #[derive(Debug, PartialEq, Eq, Clone)]
 enum Module {
     Broadcast {
-        name: String,
-        descendants: Vec<String>,
+        name: Symbol,
+        descendants: Vec<Symbol>,
     },
     FlipFlop {
-        name: String,
+        name: Symbol,
         is_on: bool,
-        descendants: Vec<String>,
+        descendants: Vec<Symbol>,
     },
     Conjunction {
-        name: String,
-        inputs: HashMap<String, Pulse>,
-        descendants: Vec<String>,
+        name: Symbol,
+        // Last pulse from each input; conjunctions only have a handful, so a scan is cheap.
+        inputs: Vec<(Symbol, Pulse)>,
+        descendants: Vec<Symbol>,
     },
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
 struct PulseAction {
-    source: String,
+    source: Symbol,
     pulse: Pulse,
-    destination: String,
+    destination: Symbol,
 }
 
 impl Module {
-    fn get_descendants(&self) -> &Vec<String> {
+    fn get_descendants(&self) -> &Vec<Symbol> {
         match self {
             Self::Broadcast {
                 name: _,
@@ -61,14 +65,14 @@ impl Module {
         }
     }
 
-    fn handle_pulse(&mut self, source: String, pulse: Pulse) -> Vec<PulseAction> {
+    fn handle_pulse(&mut self, source: Symbol, pulse: Pulse) -> Vec<PulseAction> {
         match self {
             Self::Broadcast { name, descendants } => descendants
                 .iter()
-                .map(|destination| PulseAction {
-                    source: name.clone(),
+                .map(|&destination| PulseAction {
+                    source: *name,
                     pulse,
-                    destination: destination.clone(),
+                    destination,
                 })
                 .collect(),
             Self::FlipFlop {
@@ -81,10 +85,10 @@ impl Module {
                 } else {
                     let results = descendants
                         .iter()
-                        .map(|destination| PulseAction {
-                            source: name.clone(),
+                        .map(|&destination| PulseAction {
+                            source: *name,
                             pulse: if *is_on { Pulse::Low } else { Pulse::High },
-                            destination: destination.clone(),
+                            destination,
                         })
                         .collect();
                     *is_on = !*is_on;
@@ -96,18 +100,22 @@ impl Module {
                 inputs,
                 descendants,
             } => {
-                inputs.insert(source.to_string(), pulse);
-                let pulse_to_send = if inputs.values().all(|&pulse| pulse == Pulse::High) {
+                let input = inputs
+                    .iter_mut()
+                    .find(|(input, _)| *input == source)
+                    .expect("Pulse from a module that isn't an input");
+                input.1 = pulse;
+                let pulse_to_send = if inputs.iter().all(|&(_, pulse)| pulse == Pulse::High) {
                     Pulse::Low
                 } else {
                     Pulse::High
                 };
                 descendants
                     .iter()
-                    .map(|destination| PulseAction {
-                        source: name.clone(),
+                    .map(|&destination| PulseAction {
+                        source: *name,
                         pulse: pulse_to_send,
-                        destination: destination.clone(),
+                        destination,
                     })
                     .collect()
             }
@@ -115,81 +123,146 @@ impl Module {
     }
 }
 
+struct Network {
+    names: NodeTable,
+    // Indexed by Symbol; None for destinations like rx that nothing is defined for.
+    modules: Vec<Option<Module>>,
+    button: Symbol,
+    broadcaster: Symbol,
+}
+
+impl Network {
+    fn module(&self, symbol: Symbol) -> Option<&Module> {
+        self.modules[symbol.index()].as_ref()
+    }
+
+    fn button_press(&self) -> PulseAction {
+        PulseAction {
+            source: self.button,
+            destination: self.broadcaster,
+            pulse: Pulse::Low,
+        }
+    }
+
+    fn deliver(&mut self, pulse: PulseAction) -> Vec<PulseAction> {
+        match &mut self.modules[pulse.destination.index()] {
+            Some(module) => module.handle_pulse(pulse.source, pulse.pulse),
+            // Some destinations may not have any additional downstreams.
+            None => vec![],
+        }
+    }
+}
+
 static MODULE_REGEX: Lazy<Regex> =
     Lazy::new(|| Regex::new(r"(?<type>[%&])?(?<name>.+) -> (?<dests>.+)$").unwrap());
-fn parse_modules(input: &str) -> HashMap<String, Module> {
-    let mut modules = HashMap::new();
+fn parse_modules(input: &str) -> Network {
+    let mut names = NodeTable::new();
+    let button = names.intern("button");
+    let mut modules = vec![];
 
     // Parse each module
     for line in input.lines() {
         let captures = MODULE_REGEX.captures(line).unwrap();
 
-        let name = captures["name"].to_string();
+        let name = names.intern(&captures["name"]);
         let descendants = captures["dests"]
             .split(", ")
-            .map(|d| d.to_string())
+            .map(|d| names.intern(d))
             .collect_vec();
 
         let new_module = match captures.name("type").map(|t| t.as_str()) {
             Some("%") => Module::FlipFlop {
-                name: name.clone(),
+                name,
                 is_on: false,
                 descendants,
             },
             Some("&") => Module::Conjunction {
-                name: name.clone(),
-                inputs: HashMap::new(),
+                name,
+                inputs: vec![],
                 descendants,
             },
             None => {
-                if name == "broadcaster" {
-                    Module::Broadcast {
-                        name: name.clone(),
-                        descendants,
-                    }
+                if &captures["name"] == "broadcaster" {
+                    Module::Broadcast { name, descendants }
                 } else {
-                    panic!("Unexpected module name {} without type", name);
+                    panic!("Unexpected module name {} without type", &captures["name"]);
                 }
             }
             _ => panic!("Unexpected capture value for module type!"),
         };
-        modules.insert(name, new_module);
+        modules.resize(names.len(), None);
+        modules[name.index()] = Some(new_module);
     }
+    modules.resize(names.len(), None);
 
-    // Initialize each broadcast module to include its predecessor
-    // How do I get around the borrow checker here?
-    for (name, module) in modules.clone().iter() {
-        for descendant in module.get_descendants() {
-            match modules.get_mut(descendant) {
-                Some(Module::Conjunction {
-                    inputs,
-                    name: _,
-                    descendants: _,
-                }) => {
-                    inputs.insert(name.clone(), Pulse::Low);
-                }
-                _ => {}
+    // Initialize each conjunction module to include its predecessors.
+    for source in 0..modules.len() {
+        let Some(module) = &modules[source] else {
+            continue;
+        };
+        for descendant in module.get_descendants().clone() {
+            if let Some(Module::Conjunction { inputs, .. }) = &mut modules[descendant.index()] {
+                inputs.push((Symbol::from_index(source), Pulse::Low));
+            }
+        }
+    }
+
+    let broadcaster = names.get("broadcaster").expect("No broadcaster");
+    return Network {
+        names,
+        modules,
+        button,
+        broadcaster,
+    };
+}
+
+// The module network as a graph over `network.names`, with an edge from the button and each
+// module to everything it sends to.
+fn build_module_graph(network: &Network) -> Graph {
+    let mut graph = Graph::directed(network.names.len());
+    let button_press = network.button_press();
+    graph.add_edge(button_press.source.into(), button_press.destination.into());
+    for (name, _) in network.names.iter() {
+        if let Some(module) = network.module(name) {
+            for descendant in module.get_descendants() {
+                graph.add_edge(name.into(), (*descendant).into());
             }
         }
     }
+    return graph;
+}
 
-    return modules;
+// Draws flip-flops as boxes and conjunctions as inverted houses, labelled with their prefixes.
+fn module_dot_graph(network: &Network) -> DotGraph {
+    let mut dot = build_module_graph(network).to_dot("day20", &network.names);
+    for (symbol, name) in network.names.iter() {
+        let Some(module) = network.module(symbol) else {
+            continue;
+        };
+        let (label, shape) = match module {
+            Module::Broadcast { .. } => (name.to_string(), "doubleoctagon"),
+            Module::FlipFlop { .. } => (format!("%{name}"), "box"),
+            Module::Conjunction { .. } => (format!("&{name}"), "invhouse"),
+        };
+        dot.node(name, &[("label", &label), ("shape", shape)]);
+    }
+    return dot;
 }
 
 impl Day for Day20 {
+    fn dot_graph(&self, input: &str) -> Option<DotGraph> {
+        return Some(module_dot_graph(&parse_modules(input)));
+    }
+
     fn part1(&self, input: &str) -> Result<Box<dyn Display>, &str> {
-        let mut modules = parse_modules(input);
+        let mut network = parse_modules(input);
 
         let mut next_pulses = VecDeque::new();
         let mut num_low_pulses = 0_i64;
         let mut num_high_pulses = 0_i64;
 
         for _ in 0..1000 {
-            next_pulses.push_back(PulseAction {
-                source: "button".to_string(),
-                destination: "broadcaster".to_string(),
-                pulse: Pulse::Low,
-            });
+            next_pulses.push_back(network.button_press());
 
             while let Some(next_pulse) = next_pulses.pop_front() {
                 match next_pulse.pulse {
@@ -201,18 +274,7 @@ impl Day for Day20 {
                     }
                 }
 
-                // Some destinations may not have any additional downstreams.
-                if !modules.contains_key(&next_pulse.destination) {
-                    continue;
-                }
-
-                for n in modules
-                    .get_mut(&next_pulse.destination)
-                    .unwrap()
-                    .handle_pulse(next_pulse.source, next_pulse.pulse)
-                {
-                    next_pulses.push_back(n);
-                }
+                next_pulses.extend(network.deliver(next_pulse));
             }
         }
 
@@ -220,79 +282,116 @@ impl Day for Day20 {
     }
 
     fn part2(&self, input: &str) -> Result<Box<dyn Display>, &str> {
-        let mut modules = parse_modules(input);
+        let mut network = parse_modules(input);
 
         let mut num_button_presses: i64 = 0;
         let mut next_pulses = VecDeque::new();
 
-        // Wiring:
-        // xc, th, pd, bp each needs to receive LOW
-        // xc, th, pd, bp each sends HIGH to zh
-        // zh sends LOW to rx
-        let mut xc_low_size = None;
-        let mut th_low_size = None;
-        let mut pd_low_size = None;
-        let mut bp_low_size = None;
+        // Wiring: rx is fed by a single conjunction, which sends LOW once every one of its
+        // inputs last sent it HIGH. Each of those inputs is a conjunction too, and sends HIGH
+        // exactly when it receives LOW.
+        let graph = build_module_graph(&network);
+        let Some(rx) = network.names.get("rx") else {
+            return Err("No module sends to rx");
+        };
+        let [final_conjunction] = graph.predecessors(rx.into()) else {
+            return Err("Expected a single module feeding rx");
+        };
+        if graph.predecessors(*final_conjunction).is_empty() {
+            return Err("The module feeding rx has no inputs");
+        }
+
+        // Presses on which each of them received LOW; two are enough to find its period.
+        let mut low_presses: HashMap<Symbol, Vec<i64>> = graph
+            .predecessors(*final_conjunction)
+            .iter()
+            .map(|&input| (Symbol::from_index(input), vec![]))
+            .collect();
 
         'main_loop: loop {
             num_button_presses += 1;
-            next_pulses.push_back(PulseAction {
-                source: "button".to_string(),
-                destination: "broadcaster".to_string(),
-                pulse: Pulse::Low,
-            });
+            next_pulses.push_back(network.button_press());
 
             while let Some(next_pulse) = next_pulses.pop_front() {
-                if next_pulse.pulse == Pulse::Low && next_pulse.destination == "rx" {
+                if next_pulse.pulse == Pulse::Low && next_pulse.destination == rx {
                     return Ok(Box::new(num_button_presses));
                 }
 
                 if next_pulse.pulse == Pulse::Low {
-                    match next_pulse.destination.as_str() {
-                        "xc" => {
-                            xc_low_size = Some(num_button_presses);
-                        }
-                        "th" => {
-                            th_low_size = Some(num_button_presses);
-                        }
-                        "pd" => {
-                            pd_low_size = Some(num_button_presses);
+                    if let Some(presses) = low_presses.get_mut(&next_pulse.destination) {
+                        if presses.last() != Some(&num_button_presses) {
+                            presses.push(num_button_presses);
                         }
-                        "bp" => {
-                            bp_low_size = Some(num_button_presses);
-                        }
-                        _ => {}
                     }
-                    if xc_low_size.is_some()
-                        && th_low_size.is_some()
-                        && pd_low_size.is_some()
-                        && bp_low_size.is_some()
-                    {
+                    if low_presses.values().all(|presses| presses.len() >= 2) {
                         break 'main_loop;
                     }
                 }
 
-                // Some destinations may not have any additional downstreams.
-                if !modules.contains_key(&next_pulse.destination) {
-                    continue;
-                }
-
-                for n in modules
-                    .get_mut(&next_pulse.destination)
-                    .unwrap()
-                    .handle_pulse(next_pulse.source, next_pulse.pulse)
-                {
-                    next_pulses.push_back(n);
-                }
+                next_pulses.extend(network.deliver(next_pulse));
             }
         }
 
-        return Ok(Box::new(
-            xc_low_size
-                .unwrap()
-                .lcm(&th_low_size.unwrap())
-                .lcm(&pd_low_size.unwrap())
-                .lcm(&bp_low_size.unwrap()),
-        ));
+        return Ok(Box::new(first_press_all_fire(&low_presses)?));
+    }
+}
+
+// Each input fires on presses first, first + period, ...; rx gets LOW once they line up.
+fn first_press_all_fire(low_presses: &HashMap<Symbol, Vec<i64>>) -> Result<i128, &'static str> {
+    let congruences = low_presses
+        .values()
+        .map(|presses| Congruence::new(presses[0] as i128, (presses[1] - presses[0]) as i128));
+    let Some(latest_first_press) = low_presses.values().map(|presses| presses[0]).max() else {
+        return Err("No inputs to wait for");
+    };
+    let all_fire = math::crt(congruences).ok_or("Inputs never fire together")?;
+
+    return Ok(all_fire.first_at_least(latest_first_press as i128));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use googletest::prelude::*;
+
+    fn part2_error(input: &str) -> Option<&str> {
+        Day20 {}.part2(input).err()
+    }
+
+    #[googletest::test]
+    fn rejects_unexpected_wiring_into_rx() {
+        expect_that!(
+            part2_error("broadcaster -> a\n%a -> b"),
+            some(eq("No module sends to rx"))
+        );
+        expect_that!(
+            part2_error("broadcaster -> a, b\n%a -> rx\n%b -> rx"),
+            some(eq("Expected a single module feeding rx"))
+        );
+        expect_that!(
+            part2_error("broadcaster -> a\n%a -> b\n&c -> rx"),
+            some(eq("The module feeding rx has no inputs"))
+        );
+    }
+
+    #[googletest::test]
+    fn finds_when_inputs_fire_together() {
+        let presses = |lists: &[[i64; 2]]| -> HashMap<Symbol, Vec<i64>> {
+            lists
+                .iter()
+                .enumerate()
+                .map(|(i, list)| (Symbol::from_index(i), list.to_vec()))
+                .collect()
+        };
+        expect_that!(first_press_all_fire(&presses(&[[3, 6], [2, 6]])), ok(eq(6)));
+        // One fires on even presses and the other on odd ones.
+        expect_that!(
+            first_press_all_fire(&presses(&[[2, 4], [1, 3]])),
+            err(eq("Inputs never fire together"))
+        );
+        expect_that!(
+            first_press_all_fire(&presses(&[])),
+            err(eq("No inputs to wait for"))
+        );
     }
 }
@@ -1,27 +1,26 @@
 use crate::{
     common::{
         direction::Direction,
-        grid::{self, Grid},
-        position::Position,
+        grid::{Grid, GridParser},
+        math::polynomial,
+        point::{Coordinates, Position},
+        position_set::PositionMap,
+        search::Bfs,
     },
-    day::Day,
-};
-use std::{
-    collections::{HashSet, VecDeque},
-    fmt::Display,
+    day::{Day, Params},
 };
+use num::BigInt;
+use std::fmt::Display;
 
 pub struct Day21 {}
 
-fn get_starting_position(grid: &Grid<char>) -> Position {
-    for (i, row) in grid.iter().enumerate() {
-        for (j, c) in row.iter().enumerate() {
-            if *c == 'S' {
-                return Position(i, j);
-            }
-        }
-    }
-    panic!("Didn't find a starting position in the grid!");
+fn parse_garden(input: &str) -> (Grid<char>, Position) {
+    let parsed = GridParser::allowing(".#S")
+        .with_marker('S')
+        .parse(input)
+        .unwrap_or_else(|e| panic!("Invalid garden: {e}"));
+    let starting_position = parsed.marker('S');
+    (parsed.grid, starting_position)
 }
 
 fn count_reachable_grids(
@@ -32,197 +31,192 @@ fn count_reachable_grids(
 ) -> i64 {
     // Color the grid in a checkerboard pattern, we can floodfill from start
     // and count the number of grids we can reach that match the starting grid's color.
-    let mut num_matching_grids = 0;
-
-    let mut visited = HashSet::new();
-    let mut next_positions = VecDeque::new();
-    next_positions.push_back((starting_position, 0));
-
-    while let Some((next_position, num_steps)) = next_positions.pop_front() {
-        if visited.contains(&next_position) {
-            continue;
-        } else if grid[next_position.0][next_position.1] == '#' {
-            continue;
-        } else if num_steps > max_num_steps {
-            continue;
-        }
-        visited.insert(next_position);
-
-        if num_steps % 2 == polarity {
-            num_matching_grids += 1;
-        }
-
-        for direction in [
-            Direction::North,
-            Direction::South,
-            Direction::East,
-            Direction::West,
-        ] {
-            if let Some(neighbor) = next_position.step_within_grid(direction, &grid) {
-                next_positions.push_back((neighbor, num_steps + 1));
-            }
-        }
+    if grid[starting_position.0][starting_position.1] == '#' {
+        return 0;
     }
-    return num_matching_grids;
+    let reachable = Bfs::with_store([starting_position], PositionMap::for_grid(grid))
+        .with_max_depth(max_num_steps as usize)
+        .run(|position: &Position| {
+            [
+                Direction::North,
+                Direction::South,
+                Direction::East,
+                Direction::West,
+            ]
+            .into_iter()
+            .filter_map(|direction| position.step_within_grid(direction, grid))
+            .filter(|neighbor| grid[neighbor.0][neighbor.1] != '#')
+            .collect::<Vec<_>>()
+        });
+    return reachable.count_with_parity(polarity as usize) as i64;
 }
 
-fn count_reachable_grids_repeating_infinitely(
+// Plots reachable in exactly `num_steps` steps when the garden repeats in every direction, found
+// by walking the tiled garden directly. Only practical for a few grid lengths' worth of steps.
+fn count_reachable_in_tiled_garden(
     grid: &Grid<char>,
     starting_position: Position,
-    max_num_steps: i64,
-) -> i64 {
-    dbg!(max_num_steps);
-
-    // Looking at the input, the grid is a square, and there's always a Manhattan way to get around the grid,
-    // so we just need to calculate how many grids we can fully traverse and how far we can go along the edges.
-    let grid_length = grid.len() as i64;
-    let half_grid_length = starting_position.1 as i64;
-
-    let mut num_total_cells = 0_i64;
-
-    // Since the grid is 131 x 131, we need to consider both colors in the grid.
-    let reachable_squares_with_same_coloring =
-        count_reachable_grids(&grid, starting_position, 0, i64::MAX) as i64;
-    dbg!(reachable_squares_with_same_coloring);
-    let reachable_squares_with_opposite_coloring =
-        count_reachable_grids(&grid, starting_position, 1, i64::MAX) as i64;
-    dbg!(reachable_squares_with_opposite_coloring);
-
-    // Along each horizontal and vertical axis, how many grids can we fully traverse?
-    // 1-indexed
-    println!("On axes");
-    let n_grid_we_can_just_reach_on_axes = (max_num_steps - half_grid_length - 1) / grid_length + 1;
-    dbg!(n_grid_we_can_just_reach_on_axes);
-    let num_steps =
-        (n_grid_we_can_just_reach_on_axes - 1) * grid_length + half_grid_length as i64 + 1;
-    let num_steps_remaining = max_num_steps - num_steps;
-    dbg!(num_steps_remaining);
-
-    for position in [
-        Position(starting_position.0, (grid_length - 1) as usize),
-        Position(starting_position.0, 0),
-        Position((grid_length - 1) as usize, starting_position.1),
-        Position(0, starting_position.1),
-    ] {
-        // We can't fully traverse the grid at that position.
-        let num_cells_in_end_grid = count_reachable_grids(
-            grid,
-            position,
-            n_grid_we_can_just_reach_on_axes % 2,
-            num_steps_remaining,
-        );
-        dbg!(num_cells_in_end_grid);
-
-        // We also may not be able to fully traverse the grid just before it.
-        let num_steps_remaining = num_steps_remaining + grid_length;
-        let num_cells_in_grid_before_end = count_reachable_grids(
-            grid,
-            position,
-            (n_grid_we_can_just_reach_on_axes + 1) % 2,
-            num_steps_remaining,
-        );
-        dbg!(num_cells_in_grid_before_end);
-
-        num_total_cells += num_cells_in_end_grid + num_cells_in_grid_before_end;
-
-        // For the fully-traversed grids on that side, add up their cells.
-        // Opposite coloring
-        let num_fully_traversed_squares_with_opposite_coloring =
-            (n_grid_we_can_just_reach_on_axes - 1) / 2;
-        dbg!(num_fully_traversed_squares_with_opposite_coloring);
-        num_total_cells += num_fully_traversed_squares_with_opposite_coloring
-            * reachable_squares_with_opposite_coloring;
-        // Same coloring
-        let num_fully_traversed_squares_with_same_coloring =
-            (n_grid_we_can_just_reach_on_axes - 2) / 2;
-        dbg!(num_fully_traversed_squares_with_same_coloring);
-        num_total_cells +=
-            num_fully_traversed_squares_with_same_coloring * reachable_squares_with_same_coloring;
-    }
+    num_steps: usize,
+) -> usize {
+    let (num_rows, num_columns) = (grid.len() as i64, grid[0].len() as i64);
+    let is_plot = |c: &Coordinates| {
+        grid[c.0.rem_euclid(num_rows) as usize][c.1.rem_euclid(num_columns) as usize] != '#'
+    };
+    let reachable = Bfs::new([starting_position.cast::<i64>().unwrap()])
+        .with_max_depth(num_steps)
+        .run(|coordinates: &Coordinates| {
+            [
+                Direction::North,
+                Direction::South,
+                Direction::East,
+                Direction::West,
+            ]
+            .into_iter()
+            .filter_map(|direction| coordinates.step(direction))
+            .filter(is_plot)
+            .collect::<Vec<_>>()
+        });
+    return reachable.count_with_parity(num_steps);
+}
 
-    // On each diagonal, how many grids can we fully traverse?
-    println!("On diagonals");
-    let n_grids_we_can_just_reach_diagonally =
-        (max_num_steps - 2 * half_grid_length - 2) / grid_length + 1;
-    dbg!(n_grids_we_can_just_reach_diagonally);
-    let num_steps =
-        (n_grids_we_can_just_reach_diagonally - 1) * grid_length + half_grid_length as i64 * 2 + 2;
-    let num_steps_remaining = max_num_steps - num_steps;
-    dbg!(num_steps_remaining);
-
-    for position in [
-        Position(0, (grid_length - 1) as usize),
-        Position(0, 0),
-        Position((grid_length - 1) as usize, 0),
-        Position((grid_length - 1) as usize, (grid_length - 1) as usize),
-    ] {
-        // We can't fully traverse the grid at that position.
-        let num_cells_in_end_grid = count_reachable_grids(
-            grid,
-            position,
-            (n_grids_we_can_just_reach_diagonally + 1) % 2,
-            num_steps_remaining,
-        );
-        dbg!(num_cells_in_end_grid);
-
-        // We also may not be able to fully traverse the grid just before it.
-        let num_steps_remaining = num_steps_remaining + grid_length;
-        let num_cells_in_grid_before_end = count_reachable_grids(
-            grid,
-            position,
-            n_grid_we_can_just_reach_on_axes % 2,
-            num_steps_remaining,
-        );
-        dbg!(num_cells_in_grid_before_end);
-
-        num_total_cells += num_cells_in_end_grid * n_grids_we_can_just_reach_diagonally
-            + num_cells_in_grid_before_end * (n_grids_we_can_just_reach_diagonally - 1);
-
-        // For the fully-traversed grids on that diagonal, add up their cells.
-        // Same coloring
-        let num_fully_traversed_squares_with_same_coloring =
-            (n_grid_we_can_just_reach_on_axes - 1) * (n_grid_we_can_just_reach_on_axes - 1) / 4;
-        dbg!(num_fully_traversed_squares_with_same_coloring);
-        num_total_cells +=
-            num_fully_traversed_squares_with_same_coloring * reachable_squares_with_same_coloring;
-
-        // Opposite coloring
-        let num_fully_traversed_squares_with_opposite_coloring =
-            (n_grid_we_can_just_reach_on_axes - 3) * (n_grid_we_can_just_reach_on_axes - 1) / 4;
-        dbg!(num_fully_traversed_squares_with_opposite_coloring);
-        num_total_cells += num_fully_traversed_squares_with_opposite_coloring
-            * reachable_squares_with_opposite_coloring;
+// Extends plot counts sampled every grid length to `num_grid_lengths` grid lengths, as long as
+// the samples really do grow quadratically.
+fn extrapolate_quadratic_growth(
+    samples: &[BigInt],
+    num_grid_lengths: usize,
+) -> Result<BigInt, &'static str> {
+    match polynomial::minimal_degree(samples) {
+        Some(degree) if degree <= 2 => {}
+        _ => return Err("Reachable plots don't grow quadratically"),
     }
+    return Ok(polynomial::extrapolate(
+        samples,
+        &BigInt::from(num_grid_lengths),
+    ));
+}
 
-    // We can fully traverse the center cell.
-    num_total_cells += reachable_squares_with_same_coloring;
-
-    return num_total_cells;
+fn count_reachable_grids_repeating_infinitely(
+    grid: &Grid<char>,
+    starting_position: Position,
+    max_num_steps: usize,
+) -> Result<BigInt, &'static str> {
+    // When the garden is square and the row and column through 'S' are clear, as in the input,
+    // every grid length the walk reaches one more copy of the garden in each direction, and the
+    // number of reachable plots grows quadratically in the number of copies crossed. Sample a
+    // few and extrapolate.
+    if grid.iter().any(|row| row.len() != grid.len()) {
+        return Err("Garden isn't square");
+    }
+    let (start_row, start_column) = (starting_position.0, starting_position.1);
+    if grid[start_row].contains(&'#') || grid.iter().any(|row| row[start_column] == '#') {
+        return Err("Row or column through the start has rocks");
+    }
+    let grid_length = grid.len();
+    let remainder = max_num_steps % grid_length;
+    let samples: Vec<BigInt> = (0..4)
+        .map(|n| {
+            count_reachable_in_tiled_garden(grid, starting_position, remainder + n * grid_length)
+        })
+        .map(BigInt::from)
+        .collect();
+    return extrapolate_quadratic_growth(&samples, max_num_steps / grid_length);
 }
 
 impl Day for Day21 {
+    fn default_params(&self) -> Params {
+        return Params::new()
+            .with("part1_steps", 64)
+            .with("part2_steps", 26501365);
+    }
+
     fn part1(&self, input: &str) -> Result<Box<dyn Display>, &str> {
-        let grid = grid::parse_grid(input);
-        let starting_position = get_starting_position(&grid);
+        return self.part1_with_params(input, &self.default_params());
+    }
+
+    fn part1_with_params(&self, input: &str, params: &Params) -> Result<Box<dyn Display>, &str> {
+        let num_steps: i64 = params.get_or_panic("part1_steps");
+        let (grid, starting_position) = parse_garden(input);
 
         return Ok(Box::new(count_reachable_grids(
             &grid,
             starting_position,
-            0,
-            64,
+            num_steps % 2,
+            num_steps,
         )));
     }
 
     fn part2(&self, input: &str) -> Result<Box<dyn Display>, &str> {
-        let grid = grid::parse_grid(input);
-        let starting_position = get_starting_position(&grid);
+        return self.part2_with_params(input, &self.default_params());
+    }
 
-        dbg!(starting_position);
+    fn part2_with_params(&self, input: &str, params: &Params) -> Result<Box<dyn Display>, &str> {
+        let num_steps: usize = params.get_or_panic("part2_steps");
+        let (grid, starting_position) = parse_garden(input);
 
         return Ok(Box::new(count_reachable_grids_repeating_infinitely(
             &grid,
             starting_position,
-            26501365,
-        )));
+            num_steps,
+        )?));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use googletest::prelude::*;
+
+    #[googletest::test]
+    fn counts_plots_reachable_in_configured_steps() {
+        let input = "...........
+.....###.#.
+.###.##..#.
+..#.#...#..
+....#.#....
+.##..S####.
+.##..#...#.
+.......##..
+.##.#.####.
+.##..##.##.
+...........";
+        let params = Day21 {}.default_params().with("part1_steps", 6);
+        expect_that!(
+            Day21 {}
+                .part1_with_params(input, &params)
+                .unwrap()
+                .to_string(),
+            eq("16")
+        );
+    }
+
+    #[googletest::test]
+    fn needs_a_square_garden_with_clear_lines_through_the_start() {
+        let (grid, start) = parse_garden("...\n.S.\n...\n...");
+        expect_that!(
+            count_reachable_grids_repeating_infinitely(&grid, start, 100),
+            err(eq("Garden isn't square"))
+        );
+        let (grid, start) = parse_garden("...\n.S#\n...");
+        expect_that!(
+            count_reachable_grids_repeating_infinitely(&grid, start, 100),
+            err(eq("Row or column through the start has rocks"))
+        );
+        let (grid, start) = parse_garden(".#.\n.S.\n...");
+        expect_that!(
+            count_reachable_grids_repeating_infinitely(&grid, start, 100),
+            err(eq("Row or column through the start has rocks"))
+        );
+    }
+
+    #[googletest::test]
+    fn extrapolates_only_quadratic_growth() {
+        let quadratic: Vec<BigInt> = [1, 4, 9, 16].map(BigInt::from).to_vec();
+        expect_that!(
+            extrapolate_quadratic_growth(&quadratic, 10),
+            ok(eq(BigInt::from(121)))
+        );
+
+        let cubic: Vec<BigInt> = [0, 1, 8, 27].map(BigInt::from).to_vec();
+        expect_that!(extrapolate_quadratic_growth(&cubic, 10), err(anything()));
     }
 }
@@ -1,8 +1,11 @@
 use itertools::Itertools;
 
+use crate::common::cuboid::{Axis, Cuboid};
+use crate::common::graph::{Graph, NodeId};
+use crate::common::point::Point3;
 use crate::day::Day;
 use std::{
-    collections::{HashMap, HashSet, VecDeque},
+    collections::{HashMap, HashSet},
     fmt::Display,
 };
 
@@ -13,31 +16,31 @@ type BrickId = usize;
 #[derive(Debug, Clone)]
 struct Brick {
     brick_id: BrickId,
-    // (x, y, z)
-    start: (u32, u32, u32),
-    end: (u32, u32, u32),
+    cuboid: Cuboid<u32>,
 }
 
 // Vec will be sorted by Z axis of the start piece.
 fn parse_bricks(input: &str) -> Vec<Brick> {
     let mut bricks = vec![];
     for (brick_id, line) in input.lines().enumerate() {
-        let (start, end) = line.split_once('~').unwrap();
+        let (start, end) = line
+            .split('~')
+            .map(|corner| {
+                let (x, y, z) = corner
+                    .split(',')
+                    .map(|i| i.parse().unwrap())
+                    .collect_tuple()
+                    .unwrap();
+                Point3(x, y, z)
+            })
+            .collect_tuple()
+            .unwrap();
         bricks.push(Brick {
             brick_id,
-            start: start
-                .split(',')
-                .map(|i| i.parse().unwrap())
-                .collect_tuple()
-                .unwrap(),
-            end: end
-                .split(',')
-                .map(|i| i.parse().unwrap())
-                .collect_tuple()
-                .unwrap(),
+            cuboid: Cuboid::new(start, end),
         });
     }
-    bricks.sort_by(|a, b| a.start.2.cmp(&b.start.2));
+    bricks.sort_by(|a, b| a.cuboid.min.2.cmp(&b.cuboid.min.2));
     return bricks;
 }
 
@@ -47,15 +50,14 @@ fn place_brick_at_layer(
     brick: &Brick,
     current_layer: u32,
 ) {
-    let new_end = current_layer + brick.end.2 - brick.start.2;
-
-    for i in brick.start.0..=brick.end.0 {
-        for j in brick.start.1..=brick.end.1 {
-            layers
-                .entry(i)
-                .or_insert(HashMap::new())
-                .insert(j, (new_end, brick.brick_id));
-        }
+    let (bottom, top) = brick.cuboid.extent(Axis::Z);
+    let new_end = current_layer + top - bottom;
+
+    for footprint in brick.cuboid.project(Axis::Z).points() {
+        layers
+            .entry(footprint.0)
+            .or_insert(HashMap::new())
+            .insert(footprint.1, (new_end, brick.brick_id));
     }
 }
 
@@ -63,22 +65,22 @@ fn get_layer_for_brick(
     brick: &Brick,
     layers: &HashMap<u32, HashMap<u32, (u32, BrickId)>>,
 ) -> (u32, HashSet<BrickId>) {
-    if brick.start.2 == 1 {
+    if brick.cuboid.min.2 == 1 {
         return (1, HashSet::new());
     }
 
     let mut highest_layer = 0_u32;
     let mut supporting_brick_ids = HashSet::new();
-    for i in brick.start.0..=brick.end.0 {
-        for j in brick.start.1..=brick.end.1 {
-            if let Some((layer, brick_id)) = layers.get(&i).and_then(|js| js.get(&j)) {
-                if *layer > highest_layer {
-                    highest_layer = *layer;
-                    supporting_brick_ids.clear();
-                    supporting_brick_ids.insert(*brick_id);
-                } else if *layer == highest_layer {
-                    supporting_brick_ids.insert(*brick_id);
-                }
+    for footprint in brick.cuboid.project(Axis::Z).points() {
+        if let Some((layer, brick_id)) =
+            layers.get(&footprint.0).and_then(|js| js.get(&footprint.1))
+        {
+            if *layer > highest_layer {
+                highest_layer = *layer;
+                supporting_brick_ids.clear();
+                supporting_brick_ids.insert(*brick_id);
+            } else if *layer == highest_layer {
+                supporting_brick_ids.insert(*brick_id);
             }
         }
     }
@@ -86,106 +88,85 @@ fn get_layer_for_brick(
     return (highest_layer + 1, supporting_brick_ids);
 }
 
-fn convert_supporting_bricks(
-    bricks_supported_by_brick: &HashMap<BrickId, HashSet<BrickId>>,
-) -> HashMap<BrickId, HashSet<BrickId>> {
-    let mut bricks_supporting_other_bricks = HashMap::new();
-
-    for (&k, vs) in bricks_supported_by_brick.iter() {
-        for &v in vs {
-            bricks_supporting_other_bricks
-                .entry(v)
-                .or_insert(HashSet::new())
-                .insert(k);
+const GROUND: NodeId = 0;
+
+fn brick_node(brick_id: BrickId) -> NodeId {
+    brick_id + 1
+}
+
+// Lets the bricks fall, then returns which rests on which: an edge from each brick, or the
+// ground, to every brick it holds up.
+fn build_support_graph(bricks: &[Brick]) -> Graph {
+    let mut support_graph = Graph::directed(bricks.len() + 1);
+
+    // Simulate bricks falling and place them in a set of layers.
+    let mut layers = HashMap::new(); // x => y => z
+    for brick in bricks.iter() {
+        let (layer, supporting_bricks) = get_layer_for_brick(brick, &layers);
+
+        place_brick_at_layer(&mut layers, brick, layer);
+
+        if supporting_bricks.is_empty() {
+            support_graph.add_edge(GROUND, brick_node(brick.brick_id));
+        }
+        for supporting_brick in supporting_bricks {
+            support_graph.add_edge(brick_node(supporting_brick), brick_node(brick.brick_id));
         }
     }
 
-    return bricks_supporting_other_bricks;
+    return support_graph;
 }
 
-fn will_brick_fall_with_support_removed(
-    brick_id: BrickId,
-    bricks_removed: &HashSet<BrickId>,
-    bricks_supporting_bricks: &HashMap<BrickId, HashSet<BrickId>>,
-) -> bool {
-    let supports = bricks_supporting_bricks.get(&brick_id).unwrap();
-    return supports.intersection(bricks_removed).count() == supports.len();
+// How many bricks fall, including itself, when each node is removed. A brick falls exactly
+// when every chain of supports down to the ground passes through the removed one, i.e. when
+// the removed brick dominates it.
+fn count_falling_bricks(support_graph: &Graph) -> Vec<usize> {
+    return support_graph.dominators(GROUND).dominated_counts();
 }
 
 impl Day for Day22 {
     fn part1(&self, input: &str) -> Result<Box<dyn Display>, &str> {
         let bricks = parse_bricks(input);
+        let falling_bricks = count_falling_bricks(&build_support_graph(&bricks));
 
-        // Simulate bricks falling and place them in a set of layers.
-        let mut layers = HashMap::new(); // x => y => z
-        let mut unique_bricks_supporting_others = HashSet::<BrickId>::new();
-        for brick in bricks.iter() {
-            let (layer, supporting_bricks) = get_layer_for_brick(brick, &layers);
-
-            place_brick_at_layer(&mut layers, brick, layer);
-
-            // If only one brick is supporting the one being placed, then we cannot remove the supporting brick.
-            if supporting_bricks.len() == 1 {
-                unique_bricks_supporting_others.extend(supporting_bricks.iter());
-            }
-        }
-
-        let answer = bricks.len() - unique_bricks_supporting_others.len();
+        // Bricks that only bring themselves down can be disintegrated safely.
+        let answer = bricks
+            .iter()
+            .filter(|brick| falling_bricks[brick_node(brick.brick_id)] == 1)
+            .count();
 
         return Ok(Box::new(answer));
     }
 
     fn part2(&self, input: &str) -> Result<Box<dyn Display>, &str> {
         let bricks = parse_bricks(input);
+        let falling_bricks = count_falling_bricks(&build_support_graph(&bricks));
 
-        // Simulate bricks falling and place them in a set of layers.
-        let mut layers = HashMap::new(); // x => y => z
-
-        let mut bricks_supported_by_brick = HashMap::<BrickId, HashSet<BrickId>>::new();
-
-        for brick in bricks.iter() {
-            let (layer, supporting_bricks) = get_layer_for_brick(brick, &layers);
-
-            place_brick_at_layer(&mut layers, brick, layer);
-
-            // If only one brick is supporting the one being placed, then when it's disintegrated, things will fall
-            for i in supporting_bricks {
-                bricks_supported_by_brick
-                    .entry(i)
-                    .or_insert(HashSet::new())
-                    .insert(brick.brick_id);
-            }
-        }
-        let bricks_supporting_bricks = convert_supporting_bricks(&bricks_supported_by_brick);
-
-        let mut num_falling_bricks = 0;
-
-        for brick in 0..bricks.len() {
-            let mut fallen_bricks = HashSet::new();
-            fallen_bricks.insert(brick);
-
-            let mut bricks_to_check = VecDeque::new();
-            bricks_to_check.push_back(brick);
-            while let Some(brick_to_check) = bricks_to_check.pop_front() {
-                if let Some(supported_bricks) = bricks_supported_by_brick.get(&brick_to_check) {
-                    for supported in supported_bricks {
-                        if !fallen_bricks.contains(supported)
-                            && will_brick_fall_with_support_removed(
-                                *supported,
-                                &fallen_bricks,
-                                &bricks_supporting_bricks,
-                            )
-                        {
-                            fallen_bricks.insert(*supported);
-                            bricks_to_check.push_back(*supported);
-                        }
-                    }
-                }
-            }
-
-            num_falling_bricks += fallen_bricks.len() - 1;
-        }
+        let num_falling_bricks: usize = bricks
+            .iter()
+            .map(|brick| falling_bricks[brick_node(brick.brick_id)] - 1)
+            .sum();
 
         return Ok(Box::new(num_falling_bricks));
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use googletest::prelude::*;
+
+    const EXAMPLE: &str = "1,0,1~1,2,1
+0,0,2~2,0,2
+0,2,3~2,2,3
+0,0,4~0,2,4
+2,0,5~2,2,5
+0,1,6~2,1,6
+1,1,8~1,1,9";
+
+    #[googletest::test]
+    fn counts_falling_bricks() {
+        expect_that!(Day22 {}.part1(EXAMPLE).unwrap().to_string(), eq("5"));
+        expect_that!(Day22 {}.part2(EXAMPLE).unwrap().to_string(), eq("7"));
+    }
+}
@@ -1,11 +1,13 @@
+use crate::common::direction::Direction8;
 use crate::common::grid;
+use crate::common::point::{Point, Position};
 use crate::day::Day;
 use std::{collections::HashMap, fmt::Display};
 
 #[derive(PartialEq, Eq, Debug, Hash)]
 struct Gear {
     character: char,
-    position: (usize, usize),
+    position: Position,
 }
 
 pub struct Day3 {}
@@ -16,23 +18,23 @@ fn get_adjacent_gear(
     line_number: i32,
     grid: &Vec<Vec<char>>,
 ) -> Option<Gear> {
-    let left = std::cmp::max(0, starting_index - 1) as usize;
-    let right = std::cmp::min(ending_index + 1, grid[0].len() as i32 - 1) as usize;
-    let first_line = std::cmp::max(0, line_number - 1) as usize;
-    let last_line = std::cmp::min(line_number + 1, grid.len() as i32 - 1) as usize;
-
-    for i in first_line..=last_line {
-        for j in left..=right {
-            let c = grid[i][j];
-            if !c.is_digit(10) && c != '.' {
-                return Some(Gear {
-                    character: c,
-                    position: (i, j),
-                });
-            }
-        }
-    }
-    return None;
+    // Take the first adjacent symbol in reading order.
+    (starting_index as usize..=ending_index as usize)
+        .flat_map(|j| {
+            Direction8::ALL
+                .iter()
+                .filter_map(move |&direction| Point(line_number as usize, j).step8(direction))
+        })
+        .filter(|neighbor| neighbor.is_in_bounds(grid))
+        .filter(|neighbor| {
+            let c = grid[neighbor.0][neighbor.1];
+            !c.is_ascii_digit() && c != '.'
+        })
+        .min()
+        .map(|position| Gear {
+            character: grid[position.0][position.1],
+            position,
+        })
 }
 
 impl Day for Day3 {
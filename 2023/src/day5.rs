@@ -1,47 +1,32 @@
+use crate::common::interval::{Interval, IntervalSet, RangeMap};
 use crate::day::Day;
 use std::fmt::Display;
 
 pub struct Day5 {}
 
-#[derive(Debug)]
-struct Range {
-    source_start: u64,
-    dest_start: u64,
-    length: u64,
-}
-
 #[derive(Default, Debug)]
-struct Ranges {
-    ranges: Vec<Range>,
+struct Almanac {
+    seed_to_soil: RangeMap<u64>,
+    soil_to_fertilizer: RangeMap<u64>,
+    fertilizer_to_water: RangeMap<u64>,
+    water_to_light: RangeMap<u64>,
+    light_to_temperature: RangeMap<u64>,
+    temperature_to_humidity: RangeMap<u64>,
+    humidity_to_location: RangeMap<u64>,
 }
 
-impl Ranges {
-    fn get_range_containing(&self, input: u64) -> Option<&Range> {
-        for range in &self.ranges {
-            if input >= range.source_start && input < range.source_start + range.length {
-                return Some(range);
-            }
-        }
-        return None;
+impl Almanac {
+    fn maps(&self) -> [&RangeMap<u64>; 7] {
+        [
+            &self.seed_to_soil,
+            &self.soil_to_fertilizer,
+            &self.fertilizer_to_water,
+            &self.water_to_light,
+            &self.light_to_temperature,
+            &self.temperature_to_humidity,
+            &self.humidity_to_location,
+        ]
     }
-
-    fn get_destination(&self, input: u64) -> u64 {
-        if let Some(range) = self.get_range_containing(input) {
-            return range.dest_start + (input - range.source_start);
-        }
-        return input;
-    }
-}
-
-#[derive(Default, Debug)]
-struct Almanac {
-    seed_to_soil: Ranges,
-    soil_to_fertilizer: Ranges,
-    fertilizer_to_water: Ranges,
-    water_to_light: Ranges,
-    light_to_temperature: Ranges,
-    temperature_to_humidity: Ranges,
-    humidity_to_location: Ranges,
 }
 
 impl Day for Day5 {
@@ -51,13 +36,12 @@ impl Day for Day5 {
 
         let lowest_location = seeds
             .iter()
-            .map(|seed| almanac.seed_to_soil.get_destination(*seed))
-            .map(|soil| almanac.soil_to_fertilizer.get_destination(soil))
-            .map(|fertilizer| almanac.fertilizer_to_water.get_destination(fertilizer))
-            .map(|water| almanac.water_to_light.get_destination(water))
-            .map(|light| almanac.light_to_temperature.get_destination(light))
-            .map(|temperature| almanac.temperature_to_humidity.get_destination(temperature))
-            .map(|humidity| almanac.humidity_to_location.get_destination(humidity))
+            .map(|&seed| {
+                almanac
+                    .maps()
+                    .iter()
+                    .fold(seed, |value, map| map.get(value))
+            })
             .min();
 
         return Ok(Box::new(lowest_location.unwrap()));
@@ -67,24 +51,15 @@ impl Day for Day5 {
         let seeds: Vec<u64> = parse_seeds(input);
         let almanac = parse_almanac_or_panic(input);
 
-        let lowest_location = seeds
+        let seed_ranges: IntervalSet<u64> = seeds
+            .chunks(2)
+            .map(|pair| Interval::with_len(pair[0], pair[1]))
+            .collect();
+        let locations = almanac
+            .maps()
             .iter()
-            .step_by(2)
-            .zip(seeds.iter().skip(1).step_by(2))
-            .map(|(seed_start, length)| {
-                let range = *seed_start..(seed_start + length);
-                return range
-                    .map(|seed| almanac.seed_to_soil.get_destination(seed))
-                    .map(|soil| almanac.soil_to_fertilizer.get_destination(soil))
-                    .map(|fertilizer| almanac.fertilizer_to_water.get_destination(fertilizer))
-                    .map(|water| almanac.water_to_light.get_destination(water))
-                    .map(|light| almanac.light_to_temperature.get_destination(light))
-                    .map(|temperature| almanac.temperature_to_humidity.get_destination(temperature))
-                    .map(|humidity| almanac.humidity_to_location.get_destination(humidity))
-                    .min()
-                    .unwrap();
-            })
-            .min();
+            .fold(seed_ranges, |values, map| map.map_set(&values));
+        let lowest_location = locations.min();
 
         return Ok(Box::new(lowest_location.unwrap()));
     }
@@ -109,38 +84,47 @@ fn parse_almanac_or_panic(input: &str) -> Almanac {
     lines.next();
     lines.next();
 
-    while let Some(range) = lines.next().and_then(|l| parse_range(l)) {
-        almanac.seed_to_soil.ranges.push(range);
+    while let Some((source, destination_start)) = lines.next().and_then(|l| parse_range(l)) {
+        almanac.seed_to_soil.insert(source, destination_start);
     }
     lines.next();
-    while let Some(range) = lines.next().and_then(|l| parse_range(l)) {
-        almanac.soil_to_fertilizer.ranges.push(range);
+    while let Some((source, destination_start)) = lines.next().and_then(|l| parse_range(l)) {
+        almanac.soil_to_fertilizer.insert(source, destination_start);
     }
     lines.next();
-    while let Some(range) = lines.next().and_then(|l| parse_range(l)) {
-        almanac.fertilizer_to_water.ranges.push(range);
+    while let Some((source, destination_start)) = lines.next().and_then(|l| parse_range(l)) {
+        almanac
+            .fertilizer_to_water
+            .insert(source, destination_start);
     }
     lines.next();
-    while let Some(range) = lines.next().and_then(|l| parse_range(l)) {
-        almanac.water_to_light.ranges.push(range);
+    while let Some((source, destination_start)) = lines.next().and_then(|l| parse_range(l)) {
+        almanac.water_to_light.insert(source, destination_start);
     }
     lines.next();
-    while let Some(range) = lines.next().and_then(|l| parse_range(l)) {
-        almanac.light_to_temperature.ranges.push(range);
+    while let Some((source, destination_start)) = lines.next().and_then(|l| parse_range(l)) {
+        almanac
+            .light_to_temperature
+            .insert(source, destination_start);
     }
     lines.next();
-    while let Some(range) = lines.next().and_then(|l| parse_range(l)) {
-        almanac.temperature_to_humidity.ranges.push(range);
+    while let Some((source, destination_start)) = lines.next().and_then(|l| parse_range(l)) {
+        almanac
+            .temperature_to_humidity
+            .insert(source, destination_start);
     }
     lines.next();
-    while let Some(range) = lines.next().and_then(|l| parse_range(l)) {
-        almanac.humidity_to_location.ranges.push(range);
+    while let Some((source, destination_start)) = lines.next().and_then(|l| parse_range(l)) {
+        almanac
+            .humidity_to_location
+            .insert(source, destination_start);
     }
 
     return almanac;
 }
 
-fn parse_range(line: &str) -> Option<Range> {
+// Returns the source interval and the destination it starts mapping to.
+fn parse_range(line: &str) -> Option<(Interval<u64>, u64)> {
     if line.is_empty() {
         return None;
     }
@@ -149,11 +133,10 @@ fn parse_range(line: &str) -> Option<Range> {
         .split_ascii_whitespace()
         .map(|num| num.parse::<u64>().unwrap())
         .collect();
-    return Some(Range {
-        dest_start: range_values[0],
-        source_start: range_values[1],
-        length: range_values[2],
-    });
+    return Some((
+        Interval::with_len(range_values[1], range_values[2]),
+        range_values[0],
+    ));
 }
 
 #[cfg(test)]
@@ -1,3 +1,4 @@
+use crate::common::math;
 use crate::day::Day;
 use std::fmt::Display;
 
@@ -12,33 +13,8 @@ impl RaceRecord {
     fn num_of_winning_approaches(&self) -> u64 {
         // We're solving for x * (time - x) > distance
         // -x^2 + x*time - distance > 0
-        // x = (t +- sqrt(t^2 - 4d)) / 2
-        let sqrt_term = self.time.pow(2) as f32 - 4_f32 * self.distance as f32;
-        if sqrt_term < 0_f32 {
-            return 0;
-        }
-
-        let sqrt_term = sqrt_term.sqrt();
-        let min_solution = (self.time as f32 - sqrt_term) / 2_f32;
-        let max_solution = (self.time as f32 + sqrt_term) / 2_f32;
-
-        // Round up min_solution, round down max_solution
-        let max_time: u64 = if max_solution - max_solution.floor() < f32::EPSILON {
-            max_solution as u64 - 1
-        } else {
-            max_solution.floor() as u64
-        };
-        let min_time: u64 = if min_solution.ceil() - min_solution < f32::EPSILON {
-            min_solution as u64 + 1
-        } else {
-            min_solution.ceil() as u64
-        };
-
-        if max_time >= min_time {
-            1 + max_time - min_time
-        } else {
-            0
-        }
+        return math::count_quadratic_positive(-1, self.time as i128, -(self.distance as i128))
+            .unwrap() as u64;
     }
 }
 
@@ -163,5 +139,14 @@ mod tests {
             .num_of_winning_approaches(),
             eq(9)
         );
+
+        expect_that!(
+            RaceRecord {
+                time: 71530,
+                distance: 940200
+            }
+            .num_of_winning_approaches(),
+            eq(71503)
+        );
     }
 }
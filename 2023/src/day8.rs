@@ -1,107 +1,187 @@
-use num::Integer;
-
+use crate::common::cycle;
+use crate::common::dot::DotGraph;
+use crate::common::graph::GraphKind;
+use crate::common::intern::{Interner, Symbol};
+use crate::common::math::{self, Congruence};
 use crate::day::Day;
-use std::{collections::HashMap, fmt::Display};
+use itertools::Itertools;
+use std::fmt::Display;
 
 pub struct Day8 {}
 
-#[derive(PartialEq, Eq, Debug)]
+#[derive(PartialEq, Eq, Debug, Clone)]
 struct MapEntry {
-    left: String,
-    right: String,
+    left: Symbol,
+    right: Symbol,
 }
 
 impl MapEntry {
-    fn get_next_stop(&self, step: char) -> &str {
+    fn get_next_stop(&self, step: char) -> Symbol {
         match step {
-            'L' => &self.left,
-            'R' => &self.right,
+            'L' => self.left,
+            'R' => self.right,
             _ => panic!("Unexpected instruction"),
         }
     }
 }
 
-impl Day for Day8 {
-    fn part1(&self, input: &str) -> Result<Box<dyn Display>, &str> {
-        let mut lines = input.lines();
-
-        let mut instructions = lines.next().unwrap().chars().into_iter().cycle();
+struct Map {
+    instructions: Vec<char>,
+    nodes: Interner,
+    // Both indexed by Symbol.
+    entries: Vec<MapEntry>,
+    exits: Vec<bool>,
+}
 
-        let mut map = HashMap::new();
+impl Map {
+    fn parse(input: &str) -> Self {
+        let mut lines = input.lines();
+        let instructions = lines.next().unwrap().chars().collect();
 
+        let mut nodes = Interner::new();
+        let mut entries = vec![];
         lines.next();
-        while let Some(line) = lines.next() {
+        for line in lines {
             let items: Vec<_> = line
                 .split([' ', '=', '(', ',', ')'])
                 .filter(|s| !s.is_empty())
                 .collect();
 
-            map.insert(
-                items[0],
-                MapEntry {
-                    left: items[1].to_string(),
-                    right: items[2].to_string(),
-                },
-            );
+            let stop = nodes.intern(items[0]);
+            let entry = MapEntry {
+                left: nodes.intern(items[1]),
+                right: nodes.intern(items[2]),
+            };
+            entries.resize(nodes.len(), None);
+            entries[stop.index()] = Some(entry);
         }
+        entries.resize(nodes.len(), None);
+
+        // Every node a map entry leads to must have its own entry.
+        let entries = entries
+            .into_iter()
+            .enumerate()
+            .map(|(index, entry)| {
+                entry.unwrap_or_else(|| {
+                    panic!("No entry for {}", nodes.name(Symbol::from_index(index)))
+                })
+            })
+            .collect();
+        let exits = nodes.iter().map(|(_, name)| name.ends_with('Z')).collect();
 
-        let mut current_stop = "AAA";
-        let mut num_stops = 0;
-        while current_stop != "ZZZ" {
-            current_stop = map[current_stop].get_next_stop(instructions.next().unwrap());
-            num_stops += 1;
+        Self {
+            instructions,
+            nodes,
+            entries,
+            exits,
         }
-
-        return Ok(Box::new(num_stops));
     }
 
-    fn part2(&self, input: &str) -> Result<Box<dyn Display>, &str> {
-        let mut lines = input.lines();
+    fn node(&self, name: &str) -> Symbol {
+        self.nodes
+            .get(name)
+            .unwrap_or_else(|| panic!("No node named {name}"))
+    }
 
-        let instructions = lines.next().unwrap().chars().into_iter().cycle();
+    fn next_stop(&self, stop: Symbol, num_stops: usize) -> Symbol {
+        let instruction = self.instructions[num_stops % self.instructions.len()];
+        self.entries[stop.index()].get_next_stop(instruction)
+    }
 
-        let mut map = HashMap::new();
-        let mut starting_points = vec![];
+    // Every (stop, instruction index) a ghost leaving `start` passes through, until it repeats.
+    fn walk_until_loop(&self, start: Symbol) -> cycle::History<(Symbol, usize)> {
+        cycle::find_with_history((start, 0), |&(stop, index)| {
+            (
+                self.next_stop(stop, index),
+                (index + 1) % self.instructions.len(),
+            )
+        })
+    }
 
-        lines.next();
-        while let Some(line) = lines.next() {
-            let items: Vec<_> = line
-                .split([' ', '=', '(', ',', ')'])
-                .filter(|s| !s.is_empty())
-                .collect();
+    // Steps at which the ghost stands on a node ending in 'Z', once it's inside its loop.
+    fn arrivals_within_loop(&self, history: &cycle::History<(Symbol, usize)>) -> Vec<Congruence> {
+        let loop_start = history.cycle.start;
+        (loop_start..history.states.len())
+            .filter(|&num_stops| self.is_exit(history.states[num_stops].0))
+            .map(|num_stops| Congruence::new(num_stops as i128, history.cycle.length as i128))
+            .collect()
+    }
 
-            map.insert(
-                items[0],
-                MapEntry {
-                    left: items[1].to_string(),
-                    right: items[2].to_string(),
-                },
-            );
+    fn is_exit(&self, stop: Symbol) -> bool {
+        self.exits[stop.index()]
+    }
 
-            if items[0].ends_with('A') {
-                starting_points.push(items[0]);
+    // Starting points are drawn as boxes and exits as double circles.
+    fn to_dot(&self) -> DotGraph {
+        let mut dot = DotGraph::new("day8", GraphKind::Directed);
+        for (stop, name) in self.nodes.iter() {
+            if name.ends_with('A') {
+                dot.node(name, &[("shape", "box")]);
+            } else if self.is_exit(stop) {
+                dot.node(name, &[("shape", "doublecircle")]);
             }
+            let entry = &self.entries[stop.index()];
+            dot.edge(name, self.nodes.name(entry.left), &[("label", "L")]);
+            dot.edge(name, self.nodes.name(entry.right), &[("label", "R")]);
         }
+        dot
+    }
+}
 
-        let number_of_stops_per_point: Vec<_> = starting_points
+impl Day for Day8 {
+    fn dot_graph(&self, input: &str) -> Option<DotGraph> {
+        return Some(Map::parse(input).to_dot());
+    }
+
+    fn part1(&self, input: &str) -> Result<Box<dyn Display>, &str> {
+        let map = Map::parse(input);
+
+        let mut current_stop = map.node("AAA");
+        let destination = map.node("ZZZ");
+        let mut num_stops = 0;
+        while current_stop != destination {
+            current_stop = map.next_stop(current_stop, num_stops);
+            num_stops += 1;
+        }
+
+        return Ok(Box::new(num_stops));
+    }
+
+    fn part2(&self, input: &str) -> Result<Box<dyn Display>, &str> {
+        let map = Map::parse(input);
+
+        let histories: Vec<_> = map
+            .nodes
             .iter()
-            .map(|starting_point| {
-                let mut instr = instructions.clone();
-                let mut current_stop = *starting_point;
-                let mut num_stops = 0_i64;
-                while !current_stop.ends_with('Z') {
-                    current_stop = map[current_stop].get_next_stop(instr.next().unwrap());
-                    num_stops += 1;
-                }
-                num_stops
-            })
+            .filter(|(_, name)| name.ends_with('A'))
+            .map(|(starting_point, _)| map.walk_until_loop(starting_point))
             .collect();
 
-        return Ok(Box::new(
-            number_of_stops_per_point
+        // Before every ghost is inside its loop, just check each step directly.
+        let Some(latest_loop_start) = histories.iter().map(|h| h.cycle.start).max() else {
+            return Err("No starting nodes");
+        };
+        for num_stops in 0..latest_loop_start {
+            if histories
                 .iter()
-                .fold(1, |acc, n| acc.lcm(n)),
-        ));
+                .all(|history| map.is_exit(history.state_after(num_stops).0))
+            {
+                return Ok(Box::new(num_stops as i128));
+            }
+        }
+
+        // After that, each ghost arrives at a fixed set of offsets into its loop; try every
+        // combination of offsets and take the earliest time they all line up.
+        let num_stops = histories
+            .iter()
+            .map(|history| map.arrivals_within_loop(history))
+            .multi_cartesian_product()
+            .filter_map(math::crt)
+            .map(|all_arrive| all_arrive.first_at_least(latest_loop_start as i128))
+            .min()
+            .ok_or("Ghosts never arrive together")?;
+
+        return Ok(Box::new(num_stops));
     }
 }
 
@@ -109,4 +189,48 @@ impl Day for Day8 {
 mod tests {
     use super::*;
     use googletest::prelude::*;
+
+    #[googletest::test]
+    fn finds_when_ghosts_arrive_together() {
+        let input = "LR
+
+11A = (11B, XXX)
+11B = (XXX, 11Z)
+11Z = (11B, XXX)
+22A = (22B, XXX)
+22B = (22C, 22C)
+22C = (22Z, 22Z)
+22Z = (22B, 22B)
+XXX = (XXX, XXX)";
+        expect_that!(Day8 {}.part2(input).unwrap().to_string(), eq("6"));
+    }
+
+    #[googletest::test]
+    fn reports_ghosts_that_never_arrive_together() {
+        // The first ghost reaches an exit on odd steps and the second on even ones.
+        let input = "L
+
+11A = (11Z, 11Z)
+11Z = (11B, 11B)
+11B = (11Z, 11Z)
+22A = (22B, 22B)
+22B = (22Z, 22Z)
+22Z = (22B, 22B)";
+        expect_that!(
+            Day8 {}.part2(input).map(|answer| answer.to_string()),
+            err(eq("Ghosts never arrive together"))
+        );
+    }
+
+    #[googletest::test]
+    fn needs_a_starting_node() {
+        let input = "L
+
+11B = (11Z, 11Z)
+11Z = (11B, 11B)";
+        expect_that!(
+            Day8 {}.part2(input).map(|answer| answer.to_string()),
+            err(eq("No starting nodes"))
+        );
+    }
 }
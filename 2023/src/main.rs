@@ -1,11 +1,42 @@
+use rust_advent_2023::common::debug;
 use std::env;
 use std::fs;
 
-fn read_day_number_or_panic() -> i32 {
-    let mut args = env::args();
-    return match args.nth(1) {
-        Some(day) => day.parse().expect("Cannot parse day number"),
-        None => panic!("No argument at index 1"),
+struct Args {
+    day_number: i32,
+    debug_dir: Option<String>,
+    // "key=value" overrides for the day's parameters.
+    params: Vec<String>,
+    dump_graph_file: Option<String>,
+}
+
+fn parse_args_or_panic() -> Args {
+    let mut args = env::args().skip(1);
+    let mut day_number = None;
+    let mut debug_dir = None;
+    let mut params = vec![];
+    let mut dump_graph_file = None;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--debug" => {
+                debug_dir = Some(args.next().expect("--debug requires an output directory"));
+            }
+            "--dump-graph" => {
+                dump_graph_file = Some(args.next().expect("--dump-graph requires an output file"));
+            }
+            "--param" => {
+                params.push(args.next().expect("--param requires key=value"));
+            }
+            day => day_number = Some(day.parse().expect("Cannot parse day number")),
+        }
+    }
+
+    return Args {
+        day_number: day_number.expect("No day number provided"),
+        debug_dir,
+        params,
+        dump_graph_file,
     };
 }
 
@@ -16,18 +47,46 @@ fn read_input_or_panic(day: i32) -> String {
 }
 
 // To run: cargo run -- {x} where x is the day number.
+// Pass `--debug {dir}` to write debug output (e.g. grid images) into dir.
+// Pass `--param key=value` (repeatable) to override one of the day's puzzle constants.
+// Pass `--dump-graph {file}` to write the input as a Graphviz DOT graph, for days that have one.
 fn main() {
-    let day_number = read_day_number_or_panic();
+    let args = parse_args_or_panic();
+    let day_number = args.day_number;
     println!("Advent of Code, Day {day_number}");
 
+    if let Some(debug_dir) = args.debug_dir {
+        debug::enable_output(&debug_dir);
+    }
+
     let input = read_input_or_panic(day_number);
     let day = rust_advent_2023::get_implementation_for_day_or_panic(day_number);
 
-    match day.part1(input.as_str()) {
+    let mut params = day.default_params();
+    for assignment in &args.params {
+        params
+            .set_from_assignment(assignment)
+            .unwrap_or_else(|e| panic!("Invalid --param: {e}"));
+    }
+    for (key, value) in params.iter() {
+        println!("Parameter {key} = {value}");
+    }
+
+    if let Some(dump_graph_file) = args.dump_graph_file {
+        match day.dot_graph(input.as_str()) {
+            Some(graph) => {
+                fs::write(&dump_graph_file, graph.to_string()).expect("Cannot write graph");
+                println!("Wrote graph to {dump_graph_file}");
+            }
+            None => println!("Day {day_number} has no graph to dump"),
+        }
+    }
+
+    match day.part1_with_params(input.as_str(), &params) {
         Ok(answer) => println!("Part 1 Answer: {answer}"),
         Err(msg) => println!("Part 1 Error! {msg}"),
     };
-    match day.part2(input.as_str()) {
+    match day.part2_with_params(input.as_str(), &params) {
         Ok(answer) => println!("Part 2 Answer: {answer}"),
         Err(msg) => println!("Part 2 Error! {msg}"),
     };